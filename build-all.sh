@@ -3,4 +3,6 @@
 set -eu
 
 cargo +nightly contract build --manifest-path ticket/Cargo.toml
+# The event contract is built with its default features, which enable the resale,
+# auctions and governance subsystems. See `[features]` in Cargo.toml to drop one.
 cargo +nightly contract build
\ No newline at end of file
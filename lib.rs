@@ -2,15 +2,76 @@
 
 use ink_lang as ink;
 
+/// Extension contract registered for one or more lifecycle points of an event.
+///
+/// Every method returns `false` to reject the action it is called for.
+#[ink::trait_definition]
+pub trait LifecycleHook {
+    /// Called before the ticket ID is transferred from `from` to `to`
+    #[ink(message)]
+    fn before_transfer(
+        &mut self,
+        from: ink_env::AccountId,
+        to: ink_env::AccountId,
+        ticket_id: u32,
+    ) -> bool;
+
+    /// Called before `buyer` mints `quantity` tickets of the ticket ID
+    #[ink(message)]
+    fn before_purchase(
+        &mut self,
+        buyer: ink_env::AccountId,
+        ticket_id: u32,
+        quantity: u128,
+    ) -> bool;
+
+    /// Called before `account` claims a refund of `amount`
+    #[ink(message)]
+    fn before_refund(&mut self, account: ink_env::AccountId, amount: u128) -> bool;
+
+    /// Called after the ticket ID has been checked in on day `day` of the event.
+    /// The return value is ignored.
+    #[ink(message)]
+    fn after_check_in(&mut self, ticket_id: u32, day: u8) -> bool;
+}
+
+/// Contract computing ticket prices on behalf of an event
+#[ink::trait_definition]
+pub trait PricingStrategy {
+    /// Returns the total price of `quantity` tickets of the tier at time `now`
+    #[ink(message)]
+    fn price_for(&self, tier: u32, quantity: u128, now: u64) -> u128;
+}
+
+/// Contract reporting an external condition, e.g. rainfall or an official cancellation
+#[ink::trait_definition]
+pub trait RefundOracle {
+    /// Returns true if the condition occurred between `from` and `until`
+    #[ink(message)]
+    fn condition_met(&self, from: u64, until: u64) -> bool;
+}
+
 // Strings should be made Vec<u8> in smart contracts and then parse on the UI side when contract is started
 #[ink::contract]
 mod ticket_event {
-    use ink_prelude::string::String;
-    use ink_storage::{traits::SpreadAllocate, Mapping};
+    use ink_env::call::{build_call, Call, ExecutionInput, Selector};
+    use ink_env::hash::{Blake2x256, HashOutput};
+    use ink_lang::ToAccountId;
+    use ink_prelude::{string::String, vec::Vec};
+    use ink_storage::{
+        traits::{PackedLayout, SpreadAllocate, SpreadLayout},
+        Lazy, Mapping,
+    };
     use ticket::TicketRef;
 
     /// A ticket ID.
     pub type EventId = u32;
+    /// Sequential number of a purchase, starting at 1.
+    pub type InvoiceNumber = u64;
+    /// A section of the venue.
+    pub type SectionId = u32;
+    /// An add-on experience, bit `n` of a ticket ID's add-on set stands for add-on `n`
+    pub type AddOnId = u8;
     /// Defines the storage of all values
     #[ink(storage)]
     #[derive(SpreadAllocate)]
@@ -22,17 +83,261 @@ mod ticket_event {
         /// Mapping from owner to list of owned tickets
         balance: Mapping<AccountId, Balance>,
         /// Name of event
-        name: String,
+        name: Lazy<String>,
         /// Location of the event
-        location: String,
+        location: Lazy<String>,
         /// Symbol of event
-        symbol: String,
+        symbol: Lazy<String>,
         /// Date of event
-        date: String,
+        date: Lazy<String>,
         /// Price of ticket
         price: u32,
-        /// TicketRef
-        ticket_ref: TicketRef,
+        /// TicketRef, `None` until the `Ticket` contract has been instantiated.
+        /// Only loaded from storage by messages that use it.
+        ticket_ref: Lazy<Option<TicketRef>>,
+        /// Organizer of the event
+        owner: AccountId,
+        /// Whether tickets can currently be minted
+        sale_open: bool,
+        /// Pull-payment ledger of refunds owed to each account
+        refunds: Mapping<AccountId, Balance>,
+        /// Sum of all refunds that have not been claimed yet
+        total_refunds_owed: Balance,
+        /// Time after which unclaimed refunds can be swept by the owner
+        claims_deadline: Option<Timestamp>,
+        /// Whether unclaimed refunds have been swept, closing the ledger for good
+        refunds_swept: bool,
+        /// Accounts that have been credited a refund, in order of crediting
+        refund_accounts: Mapping<u32, AccountId>,
+        /// Number of entries in `refund_accounts`
+        refund_account_count: u32,
+        /// Next entry in `refund_accounts` to be cleaned up
+        cleanup_cursor: u32,
+        /// Start of the event, if set
+        event_time: Option<Timestamp>,
+        /// Hash of the attendee data bound to a ticket ID
+        attendee_data: Mapping<EventId, Hash>,
+        /// Ticket IDs that have had attendee data bound, in order of binding
+        attendee_ids: Mapping<u32, EventId>,
+        /// Number of entries in `attendee_ids`
+        attendee_id_count: u32,
+        /// Next entry in `attendee_ids` to be purged
+        purge_cursor: u32,
+        /// Extension contracts implementing `LifecycleHook`, by lifecycle point
+        hooks: Mapping<HookPoint, Hook>,
+        /// Whether minting and transfers are paused by the circuit breaker
+        paused: bool,
+        /// Maximum tickets minted per block before the circuit breaker trips, 0 disables
+        max_purchases_per_block: Balance,
+        /// Maximum tickets transferred per block before the circuit breaker trips, 0 disables
+        max_transfers_per_block: Balance,
+        /// Block the activity counters below belong to
+        activity_block: BlockNumber,
+        /// Tickets minted in `activity_block`
+        block_purchases: Balance,
+        /// Tickets transferred in `activity_block`
+        block_transfers: Balance,
+        /// Contract implementing `PricingStrategy`, if set
+        pricing_strategy: Option<AccountId>,
+        /// Amount of tickets sold through `buy_ticket`
+        tickets_sold: Balance,
+        /// Discount applied automatically shortly before an undersold event, if set
+        last_minute_discount: Option<LastMinuteDiscount>,
+        /// Explicit price of a single ticket of the ticket ID, overriding all other pricing
+        price_overrides: Mapping<EventId, Balance>,
+        /// Receipt of every purchase, by invoice number
+        receipts: Mapping<InvoiceNumber, Receipt>,
+        /// Invoice number of the latest purchase, 0 before the first purchase
+        invoice_count: InvoiceNumber,
+        /// Total amount paid for tickets through `buy_ticket`
+        revenue: Balance,
+        /// Whether every ticket ID is a unique ticket instead of a fungible ticket type
+        non_fungible: bool,
+        /// Tax rate in basis points charged on top of the ticket price
+        tax_rate_bps: u16,
+        /// Code of the jurisdiction the tax is remitted to
+        tax_jurisdiction: Lazy<String>,
+        /// Total tax collected through `buy_ticket`
+        tax_collected: Balance,
+        /// Price, cap and sales of every offered concession category
+        concessions: Mapping<Concession, ConcessionTerms>,
+        /// Concession category of a ticket ID in the non-fungible model
+        ticket_concession: Mapping<EventId, Concession>,
+        /// Carbon offset charged per ticket when a buyer opts in
+        carbon_offset_per_ticket: Balance,
+        /// Account the carbon offset bucket can be withdrawn to, if offsets are offered
+        carbon_offset_partner: Option<AccountId>,
+        /// Total carbon offset paid by buyers
+        carbon_offset_collected: Balance,
+        /// Carbon offset paid by buyers that has not been withdrawn yet
+        carbon_offset_bucket: Balance,
+        /// Merch vouchers held by each account
+        vouchers: Mapping<AccountId, Balance>,
+        /// Price of a single merch voucher
+        voucher_price: Balance,
+        /// Maximum amount of merch vouchers ever minted
+        max_voucher_supply: Balance,
+        /// Amount of merch vouchers minted
+        voucher_supply: Balance,
+        /// Roles granted to accounts by the owner
+        roles: Mapping<(Role, AccountId), bool>,
+        /// Price of a single parking pass
+        parking_price: Balance,
+        /// Amount of parking spaces that can be sold
+        parking_capacity: Balance,
+        /// Amount of parking passes sold
+        parking_sold: Balance,
+        /// Unused parking passes held by each account
+        parking_passes: Mapping<AccountId, Balance>,
+        /// Prepaid food and drink credit of each account
+        credits: Mapping<AccountId, Balance>,
+        /// Food and drink credit topped up and neither spent nor refunded
+        credits_outstanding: Balance,
+        /// Food and drink credit spent at vendors
+        credits_spent: Balance,
+        /// Days of the event a ticket ID is valid for as a bitmap, day 0 only if not set
+        pass_days: Mapping<EventId, u32>,
+        /// Days of the event a ticket ID has been checked in on as a bitmap
+        checked_in_days: Mapping<EventId, u32>,
+        /// Section of the venue a ticket ID belongs to, section 0 if not set
+        ticket_section: Mapping<EventId, SectionId>,
+        /// Ticket IDs currently inside the venue
+        inside: Mapping<EventId, bool>,
+        /// Amount of tickets currently inside the venue
+        attendance: u32,
+        /// Amount of tickets currently inside each section of the venue
+        section_attendance: Mapping<SectionId, u32>,
+        /// Latest check-in of each ticket ID
+        check_ins: Mapping<EventId, CheckInRecord>,
+        /// Policy for refunding tickets never checked in, if offered
+        unused_refund_policy: Option<UnusedRefundPolicy>,
+        /// Revenue set aside to refund tickets never checked in
+        unused_refund_reserve: Balance,
+        /// Price paid for each ticket ID
+        face_values: Mapping<EventId, Balance>,
+        /// Buyer of each ticket ID on the primary sale
+        original_buyers: Mapping<EventId, AccountId>,
+        /// Ticket IDs listed for resale
+        #[cfg(feature = "resale")]
+        listings: Mapping<EventId, Listing>,
+        /// Share of the resale premium above face value credited to the original buyer
+        #[cfg(feature = "resale")]
+        resale_share_bps: u16,
+        /// Minimum resale price as a share of face value
+        #[cfg(feature = "resale")]
+        resale_floor_bps: u16,
+        /// English auctions of unsold ticket IDs
+        #[cfg(feature = "auctions")]
+        auctions: Mapping<EventId, Auction>,
+        /// Amount of tickets held back from the sale for running auctions
+        auctioned_tickets: Balance,
+        /// Extension of auctions receiving late bids, if enabled
+        #[cfg(feature = "auctions")]
+        anti_sniping: Option<AntiSniping>,
+        /// Sealed-bid auctions keyed by the first ticket ID of their lot
+        #[cfg(feature = "auctions")]
+        sealed_auctions: Mapping<EventId, SealedAuction>,
+        /// First ticket ID of the sealed-bid auction lot each ticket ID belongs to
+        #[cfg(feature = "auctions")]
+        sealed_lots: Mapping<EventId, EventId>,
+        /// Committed and not yet revealed sealed bids
+        #[cfg(feature = "auctions")]
+        sealed_bids: Mapping<(EventId, AccountId), SealedBid>,
+        /// Revealed sealed bids currently winning, highest first
+        #[cfg(feature = "auctions")]
+        sealed_winners: Mapping<EventId, Vec<(AccountId, Balance)>>,
+        /// True once the event is cancelled and paid amounts are being refunded
+        cancelled: bool,
+        /// Amount collected for the tickets each account holds and not refunded
+        payments: Mapping<AccountId, Balance>,
+        /// Amount collected for each sold ticket ID: price, tax, offset and add-ons
+        ticket_payments: Mapping<EventId, Balance>,
+        /// Amount each account paid for merch vouchers and parking passes and not had refunded
+        extra_payments: Mapping<AccountId, Balance>,
+        /// Index of the accounts that paid for tickets, vouchers, parking or credit
+        payers: Mapping<u32, AccountId>,
+        /// Amount of accounts in `payers`
+        payer_count: u32,
+        /// Next index of `payers` to be refunded by `process_refunds`
+        refund_cursor: u32,
+        /// True if tickets can only be bought through the purchase queue
+        queue_mode: bool,
+        /// Purchase intents waiting to be fulfilled, in arrival order
+        purchase_queue: Mapping<u64, PurchaseIntent>,
+        /// Position of the next purchase intent to fulfill
+        queue_head: u64,
+        /// Position the next purchase intent is enqueued at
+        queue_tail: u64,
+        /// Maximum amount of tickets sold per block, 0 for no limit
+        max_tickets_per_block: Balance,
+        /// Last time the owner performed an admin action
+        last_admin_action: Timestamp,
+        /// Owner inactivity before the event after which holders can refund themselves, if enabled
+        dead_man_period: Option<Timestamp>,
+        /// Account that can veto emergency withdrawals, if set
+        #[cfg(feature = "governance")]
+        guardian: Option<AccountId>,
+        /// Announced emergency withdrawal waiting for its timelock, if any
+        #[cfg(feature = "governance")]
+        pending_withdrawal: Option<EmergencyWithdrawal>,
+        /// External condition that cancels the event when reported by its oracle, if set
+        refund_condition: Option<RefundCondition>,
+        /// Rows and seats of each seated section of the venue
+        section_layouts: Mapping<SectionId, SectionLayout>,
+        /// Ticket ID holding each seat
+        seat_tickets: Mapping<Seat, EventId>,
+        /// Seat of each seated ticket ID
+        ticket_seats: Mapping<EventId, Seat>,
+        /// Seats withdrawn from the event by a capacity reduction
+        retired_seats: Mapping<Seat, ()>,
+        /// Seat swaps requested by holders of seated ticket IDs
+        swap_requests: Mapping<EventId, SwapRequest>,
+        /// Upgrade auctions of free seats, open to holders of seated ticket IDs
+        #[cfg(feature = "auctions")]
+        upgrade_auctions: Mapping<Seat, UpgradeAuction>,
+        /// Ticket IDs that are no longer valid and why
+        ticket_status: Mapping<EventId, TicketStatus>,
+        /// Announced capacity reduction waiting for its timelock, if any
+        #[cfg(feature = "governance")]
+        pending_reduction: Option<CapacityReduction>,
+        /// Add-on experiences on offer
+        add_ons: Mapping<AddOnId, AddOnTerms>,
+        /// Add-ons bought for each ticket ID as a bitmap
+        ticket_add_ons: Mapping<EventId, u32>,
+        /// Add-ons redeemed by each ticket ID as a bitmap
+        redeemed_add_ons: Mapping<EventId, u32>,
+        /// Ticket IDs that are sponsorship slots rather than admissions
+        sponsorships: Mapping<EventId, Sponsorship>,
+        /// Transfers offered by holders and waiting for the recipient to accept
+        pending_transfers: Mapping<EventId, PendingTransfer>,
+        /// Every ticket ID ever issued in non-fungible mode, in issue order
+        issued_tickets: Mapping<u32, EventId>,
+        /// Amount of ticket IDs in `issued_tickets`
+        issued_count: u32,
+        /// Next index of `issued_tickets` hashed into the snapshot being built
+        snapshot_cursor: u32,
+        /// Running hash of the snapshot being built
+        snapshot_hash: Hash,
+        /// Amount of valid ticket IDs hashed into the snapshot being built
+        snapshot_size: u32,
+        /// Change count when the snapshot being built was started
+        snapshot_seq: u64,
+        /// Latest completed snapshot of the holder list, if any
+        snapshot: Option<SnapshotCommitment>,
+        /// Append-only log of changes to ticket IDs, by sequence number
+        changes: Mapping<u64, Change>,
+        /// Sequence number of the next change
+        change_count: u64,
+        /// Corporate accounts handing out their tickets to employees
+        corporate_accounts: Mapping<AccountId, CorporateAccount>,
+        /// Tickets each employee may still claim, by company and employee
+        sub_allowances: Mapping<(AccountId, AccountId), Balance>,
+        /// Operators allowed to move all tickets of a holder, by holder and operator
+        operators: Mapping<(AccountId, AccountId), bool>,
+        /// Time of the most recent credit to the refund ledger
+        last_credit_at: Timestamp,
+        /// Queued payments and auction bids and deposits held until they are settled or refunded
+        escrowed_total: Balance,
     }
 
     #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Copy, Clone)]
@@ -45,10 +350,854 @@ mod ticket_event {
         CannotInsert,
         CannotFetchValue,
         NotAllowed,
+        SaleNotOpen,
+        SaleAlreadyOpen,
+        CannotInstantiate,
+        NothingToClaim,
+        ClaimsClosed,
+        ClaimsStillOpen,
+        DeadlineTooSoon,
+        TransferFailed,
+        AlreadyLinked,
+        EventNotOver,
+        HookRejected,
+        HookGasTooHigh,
+        Paused,
+        SoldOut,
+        IncorrectPayment,
+        PricingFailed,
+        InvalidBasisPoints,
+        InsufficientBalance,
+        InvalidAmount,
+        ConcessionNotOffered,
+        CarbonOffsetNotOffered,
+        NonFungibleOnly,
+        NotValidToday,
+        /// Holds the time and scanner of the original check-in
+        AlreadyCheckedIn(Timestamp, AccountId),
+        NotCheckedIn,
+        TicketUsed,
+        NotListed,
+        BelowPriceFloor,
+        NoAuction,
+        AuctionEnded,
+        AuctionNotEnded,
+        BidTooLow,
+        BidNotRevealable,
+        InvalidReveal,
+        EventCancelled,
+        QueueOnly,
+        ThrottleExceeded,
+        NotGuardian,
+        NoPendingWithdrawal,
+        TimelockActive,
+        OracleFailed,
+        ConditionNotMet,
+        UnknownSection,
+        NoAdjacentSeats,
+        NoSwapRequest,
+        SeatNotWanted,
+        NoPendingReduction,
+        UnknownAddOn,
+        AddOnAlreadyUsed,
+        TransferLocked,
+        NoPendingTransfer,
+        TransferExpired,
+        NoCorporateAccount,
+        ClaimDeadlinePassed,
+        NoAllowance,
+        TicketsAllocated,
+        NoGuardian,
+        SeatsSold,
+        SeatRetired,
+    }
+
+    /// Minimum time between setting the claims deadline and the deadline itself, 90 days in milliseconds
+    pub const MIN_CLAIMS_PERIOD: Timestamp = 90 * 24 * 60 * 60 * 1000;
+
+    /// Maximum gas a single hook call may use
+    pub const MAX_HOOK_GAS: u64 = 5_000_000_000;
+
+    /// Length of a day of the event in milliseconds
+    pub const DAY: Timestamp = 24 * 60 * 60 * 1000;
+
+    /// Basis points making up 100%
+    pub const MAX_BPS: u16 = 10_000;
+
+    /// Delay between announcing and executing an emergency withdrawal, 7 days in milliseconds
+    #[cfg(feature = "governance")]
+    pub const EMERGENCY_WITHDRAWAL_DELAY: Timestamp = 7 * 24 * 60 * 60 * 1000;
+
+    /// Maximum amount of seats in a section, bounding the search for adjacent seats
+    pub const MAX_SECTION_SEATS: u32 = 500;
+
+    /// Delay between announcing and executing a capacity reduction, 2 days in milliseconds
+    #[cfg(feature = "governance")]
+    pub const CAPACITY_REDUCTION_DELAY: Timestamp = 2 * 24 * 60 * 60 * 1000;
+
+    /// Maximum amount of ticket IDs in a range refunded by one capacity reduction
+    #[cfg(feature = "governance")]
+    pub const MAX_REDUCTION_TICKETS: u32 = 500;
+
+    /// Maximum amount of ticket IDs verified by one `verify_batch` call
+    pub const MAX_VERIFY_BATCH: u32 = 100;
+
+    /// Maximum amount of changes returned by one `changes_since` call
+    pub const MAX_CHANGES_PAGE: u32 = 100;
+
+    /// Maximum amount of issued ticket IDs read by one `snapshot_chunk` or `build_snapshot` call
+    pub const MAX_SNAPSHOT_CHUNK: u32 = 500;
+
+    /// Maximum amount of ticket IDs in one sealed-bid auction, bounding its settlement
+    #[cfg(feature = "auctions")]
+    pub const MAX_SEALED_UNITS: u32 = 50;
+
+    /// All parameters needed to set up a new event
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct EventConfig {
+        /// Total amount of tickets available
+        pub total_tickets: Balance,
+        /// Version of the event, used as salt for the ticket contract
+        pub version: u32,
+        /// Name of event
+        pub name: String,
+        /// Location of the event
+        pub location: String,
+        /// Symbol of event
+        pub symbol: String,
+        /// Date of event
+        pub date: String,
+        /// Price of ticket
+        pub price: u32,
+        /// Code hash of the uploaded `Ticket` contract
+        pub ticket_ref_code_hash: Hash,
+        /// How tickets are accounted for
+        pub model: TicketModel,
+    }
+
+    /// Emitted when the owner sweeps refunds that were never claimed
+    #[ink(event)]
+    pub struct UnclaimedRefundsSwept {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Point in the lifecycle of a ticket where a hook can be registered
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum HookPoint {
+        BeforeTransfer,
+        BeforePurchase,
+        BeforeRefund,
+        AfterCheckIn,
+    }
+
+    /// Extension contract registered for a lifecycle point
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Hook {
+        /// Contract implementing `LifecycleHook`
+        pub contract: AccountId,
+        /// Gas the hook call may use, at most `MAX_HOOK_GAS`
+        pub gas_limit: u64,
+    }
+
+    /// Emitted when anomalous activity pauses minting and transfers
+    #[ink(event)]
+    pub struct CircuitBreakerTripped {
+        #[ink(topic)]
+        block: BlockNumber,
+        purchases: Balance,
+        transfers: Balance,
+    }
+
+    /// Emitted when tickets are bought
+    #[ink(event)]
+    pub struct Purchased {
+        #[ink(topic)]
+        buyer: AccountId,
+        #[ink(topic)]
+        ticket_id: EventId,
+        quantity: Balance,
+        price: Balance,
+        tax: Balance,
+        invoice: InvoiceNumber,
+    }
+
+    /// Discount applied when the event is close and not sold well
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct LastMinuteDiscount {
+        /// Time before the start of the event from which the discount can apply
+        pub window: Timestamp,
+        /// Sell-through in basis points below which the discount applies
+        pub sell_through_target_bps: u16,
+        /// Discount in basis points of the price
+        pub discount_bps: u16,
+    }
+
+    /// Record of a completed purchase
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Receipt {
+        /// Account that bought the tickets
+        pub buyer: AccountId,
+        /// Ticket ID that was bought or the sale was for, 0 for extras bought on their own
+        pub ticket_id: EventId,
+        /// Amount of tickets bought, 0 if no tickets were sold
+        pub quantity: Balance,
+        /// Total price paid, excluding tax
+        pub price: Balance,
+        /// Tax paid on the price
+        pub tax: Balance,
+        /// Concession category the tickets were bought in, if any
+        pub concession: Option<Concession>,
+        /// Carbon offset paid on top of the price
+        pub carbon_offset: Balance,
+        /// Amount of merch vouchers bought
+        pub vouchers: Balance,
+        /// Amount of parking passes bought
+        pub parking: Balance,
+        /// Food and drink credit topped up alongside the tickets
+        pub credit: Balance,
+        /// Add-on bought for the ticket ID, if the sale was an add-on
+        pub add_on: Option<AddOnId>,
+        /// Seat the ticket ID was upgraded to, if the sale was a seat upgrade
+        pub upgrade: Option<Seat>,
+        /// Time of the purchase
+        pub timestamp: Timestamp,
+    }
+
+    /// How tickets are accounted for, chosen at construction
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum TicketModel {
+        /// Tickets are interchangeable and only counted per ticket ID
+        Fungible,
+        /// Every ticket ID is a unique ticket with a single owner
+        NonFungible,
+    }
+
+    /// Reduced-price category a purchaser can select, checked at the gate
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum Concession {
+        Child,
+        Senior,
+        Student,
+    }
+
+    /// Terms and sales of a concession category
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct ConcessionTerms {
+        /// Price of a single ticket, excluding tax
+        pub price: Balance,
+        /// Maximum amount of tickets sold in the category
+        pub cap: Balance,
+        /// Amount of tickets sold in the category
+        pub sold: Balance,
+    }
+
+    /// Optional extras selected by the buyer of tickets
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Copy, Clone, Default)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct PurchaseOptions {
+        /// Concession category to buy the tickets in, if any
+        pub concession: Option<Concession>,
+        /// Whether to add the carbon offset for every ticket
+        pub carbon_offset: bool,
+        /// Amount of merch vouchers to buy alongside the tickets
+        pub vouchers: Balance,
+        /// Amount of parking passes to buy alongside the tickets
+        pub parking: Balance,
+        /// Food and drink credit to top up alongside the tickets
+        pub credit: Balance,
+    }
+
+    /// Breakdown of what a purchase costs
+    struct Charges {
+        price: Balance,
+        tax: Balance,
+        carbon_offset: Balance,
+        vouchers: Balance,
+        parking: Balance,
+        extras_tax: Balance,
+        credit: Balance,
+    }
+
+    impl Charges {
+        fn total(&self) -> Balance {
+            self.price
+                + self.tax
+                + self.carbon_offset
+                + self.vouchers
+                + self.parking
+                + self.extras_tax
+                + self.credit
+        }
+    }
+
+    /// Emitted when merch vouchers are minted
+    #[ink(event)]
+    pub struct VouchersMinted {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when a merch stand redeems merch vouchers
+    #[ink(event)]
+    pub struct VouchersRedeemed {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        merch_stand: AccountId,
+        amount: Balance,
+    }
+
+    /// Permission the owner can grant to an account
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Role {
+        /// May redeem merch vouchers
+        MerchStand,
+        /// May check in vehicles with a parking pass
+        ParkingScanner,
+        /// May spend food and drink credit of attendees
+        Vendor,
+        /// May check in tickets at the door
+        GateScanner,
+    }
+
+    /// Emitted when a vehicle enters the parking lot using a parking pass
+    #[ink(event)]
+    pub struct VehicleCheckedIn {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        scanner: AccountId,
+    }
+
+    /// Emitted when a vendor spends food and drink credit of an account
+    #[ink(event)]
+    pub struct CreditSpent {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        vendor: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when a ticket ID is checked in at the door
+    #[ink(event)]
+    pub struct CheckedIn {
+        #[ink(topic)]
+        ticket_id: EventId,
+        day: u8,
+        scanner: AccountId,
+    }
+
+    /// Emitted when a ticket ID leaves the venue
+    #[ink(event)]
+    pub struct CheckedOut {
+        #[ink(topic)]
+        ticket_id: EventId,
+        scanner: AccountId,
+    }
+
+    /// When and by whom a ticket ID was checked in
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct CheckInRecord {
+        pub timestamp: Timestamp,
+        pub scanner: AccountId,
+    }
+
+    /// Partial refund of tickets never checked in, claimable for `window` after the event started
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct UnusedRefundPolicy {
+        /// Share of the price paid that is refunded
+        pub refund_bps: u16,
+        /// Share of the revenue set aside to fund the refunds
+        pub reserve_bps: u16,
+        pub window: Timestamp,
+    }
+
+    /// Ticket ID offered for resale by its owner
+    #[cfg(feature = "resale")]
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Listing {
+        pub seller: AccountId,
+        pub price: Balance,
+    }
+
+    /// Emitted when a listed ticket ID is resold
+    #[ink(event)]
+    pub struct Resold {
+        #[ink(topic)]
+        ticket_id: EventId,
+        seller: AccountId,
+        buyer: AccountId,
+        price: Balance,
+        original_buyer_share: Balance,
+    }
+
+    /// English auction of an unsold ticket ID
+    #[cfg(feature = "auctions")]
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Auction {
+        pub reserve_price: Balance,
+        pub end: BlockNumber,
+        pub highest_bidder: Option<AccountId>,
+        pub highest_bid: Balance,
+    }
+
+    /// Bids landing within `window` blocks of the end of an auction push it back by `extension` blocks
+    #[cfg(feature = "auctions")]
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct AntiSniping {
+        pub window: BlockNumber,
+        pub extension: BlockNumber,
+    }
+
+    /// Emitted when a bid is placed in an auction
+    #[ink(event)]
+    pub struct BidPlaced {
+        #[ink(topic)]
+        ticket_id: EventId,
+        bidder: AccountId,
+        amount: Balance,
+        end: BlockNumber,
+    }
+
+    /// Commit-reveal auction of `units` ticket IDs, settled at a uniform clearing price
+    #[cfg(feature = "auctions")]
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct SealedAuction {
+        pub units: u32,
+        pub reserve_price: Balance,
+        /// Bids can be committed before this block
+        pub commit_end: BlockNumber,
+        /// Bids can be revealed from `commit_end` until this block
+        pub reveal_end: BlockNumber,
+        /// Deposits and winning bids held for the auction
+        pub escrowed: Balance,
+    }
+
+    /// Hidden bid with the deposit covering it
+    #[cfg(feature = "auctions")]
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct SealedBid {
+        pub commitment: Hash,
+        pub deposit: Balance,
+    }
+
+    /// Purchase waiting in the queue with its payment held by the contract
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct PurchaseIntent {
+        pub buyer: AccountId,
+        pub ticket_id: EventId,
+        pub quantity: Balance,
+        pub payment: Balance,
+    }
+
+    /// Withdrawal of contract funds by the owner, executable after a timelock
+    #[cfg(feature = "governance")]
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct EmergencyWithdrawal {
+        pub to: AccountId,
+        pub amount: Balance,
+        pub executable_at: Timestamp,
+    }
+
+    /// Emitted when the owner announces an emergency withdrawal
+    #[ink(event)]
+    pub struct EmergencyWithdrawalAnnounced {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        executable_at: Timestamp,
+    }
+
+    /// Emitted when an announced emergency withdrawal is vetoed or cancelled
+    #[ink(event)]
+    pub struct EmergencyWithdrawalCancelled {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Oracle whose report of its condition between `from` and `until` cancels the event
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct RefundCondition {
+        pub oracle: AccountId,
+        pub from: Timestamp,
+        pub until: Timestamp,
+    }
+
+    /// Emitted when the event is cancelled because the refund condition was reported
+    #[ink(event)]
+    pub struct RefundConditionTriggered {
+        #[ink(topic)]
+        oracle: AccountId,
+    }
+
+    /// Seated section of `rows` rows with `seats_per_row` seats each
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct SectionLayout {
+        pub rows: u32,
+        pub seats_per_row: u32,
+    }
+
+    /// Seat in a section of the venue, rows and seats are numbered from 1
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Seat {
+        pub section: SectionId,
+        pub row: u32,
+        pub number: u32,
+    }
+
+    /// Seat a holder would swap their ticket ID's seat for: any seat in rows
+    /// `min_row` to `max_row` of the section
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct SwapRequest {
+        pub holder: AccountId,
+        pub section: SectionId,
+        pub min_row: u32,
+        pub max_row: u32,
+    }
+
+    /// Emitted when two holders swap the seats of their ticket IDs
+    #[ink(event)]
+    pub struct SeatsSwapped {
+        #[ink(topic)]
+        ticket_id: EventId,
+        #[ink(topic)]
+        other_ticket_id: EventId,
+    }
+
+    /// Auction of a free seat among holders, the highest bid moves its ticket ID to the seat
+    #[cfg(feature = "auctions")]
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct UpgradeAuction {
+        pub reserve_price: Balance,
+        pub end: BlockNumber,
+        pub highest_bidder: Option<AccountId>,
+        /// Ticket ID the highest bidder wants to move to the seat
+        pub ticket_id: EventId,
+        pub highest_bid: Balance,
+    }
+
+    /// Why a ticket ID is no longer valid
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum TicketStatus {
+        /// Refunded because the capacity of the venue was reduced
+        Refunded,
+        /// Replaced by a reissued ticket ID after being reported lost
+        Revoked,
+        /// Withdrawn from sale unsold because the capacity of the venue was reduced
+        Withdrawn,
+    }
+
+    /// Tickets affected by a capacity reduction
+    #[cfg(feature = "governance")]
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum ReductionTarget {
+        /// `count` ticket IDs starting at `first`
+        TicketIds { first: EventId, count: u32 },
+        /// Every seat in the section
+        Section(SectionId),
+    }
+
+    /// Capacity reduction executable after a timelock
+    #[cfg(feature = "governance")]
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct CapacityReduction {
+        pub target: ReductionTarget,
+        pub executable_at: Timestamp,
+    }
+
+    /// Emitted when a ticket ID is refunded by a capacity reduction
+    #[ink(event)]
+    pub struct TicketRefunded {
+        #[ink(topic)]
+        ticket_id: EventId,
+        #[ink(topic)]
+        holder: AccountId,
+        amount: Balance,
+    }
+
+    /// Price and supply of an add-on experience
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct AddOnTerms {
+        pub price: Balance,
+        pub supply: u32,
+        pub sold: u32,
+    }
+
+    /// On-site branding slot sold as a ticket ID
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Sponsorship {
+        /// What the sponsor is entitled to
+        pub entitlement: String,
+        /// The slot can't change hands before this time, usually the end of the event
+        pub locked_until: Timestamp,
+    }
+
+    /// Transfer of a ticket ID waiting for the recipient to accept before `expires_at`
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct PendingTransfer {
+        pub from: AccountId,
+        pub to: AccountId,
+        pub expires_at: Timestamp,
+    }
+
+    /// Emitted when a lost ticket ID is revoked and replaced
+    #[ink(event)]
+    pub struct TicketReissued {
+        #[ink(topic)]
+        old_ticket_id: EventId,
+        #[ink(topic)]
+        new_ticket_id: EventId,
+        holder: AccountId,
+    }
+
+    /// Outcome of verifying a ticket ID against the account presenting it
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum VerifyResult {
+        /// Held by the account and not checked in today
+        Valid,
+        /// Held by another account
+        WrongHolder,
+        /// Already checked in today
+        CheckedIn,
+        Refunded,
+        Revoked,
+        NotFound,
+        /// Not valid on the current day of the event, or the event is not running
+        NotValidToday,
+        /// The event is cancelled
+        Cancelled,
+    }
+
+    /// Hash commitment to the list of valid ticket IDs and their holders.
+    ///
+    /// The hash starts out as the zero hash and is replaced by the Blake2x256 hash of the
+    /// SCALE encoded `(hash, ticket_id, holder)` for each entry of `snapshot_chunk` in order.
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct SnapshotCommitment {
+        pub hash: Hash,
+        /// Amount of valid ticket IDs in the snapshot
+        pub size: u32,
+        /// Sequence number of the first change not reflected in the snapshot
+        pub seq: u64,
+        pub timestamp: Timestamp,
+    }
+
+    /// Kind of change to a ticket ID
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum ChangeKind {
+        Mint,
+        Transfer,
+        CheckIn,
+        Refund,
+        Revoke,
+    }
+
+    /// Entry of the change log, `account` is the new holder for mints and transfers,
+    /// the scanner for check-ins and the last holder otherwise
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Change {
+        pub seq: u64,
+        pub kind: ChangeKind,
+        pub ticket_id: EventId,
+        pub account: AccountId,
+        pub block: BlockNumber,
+    }
+
+    /// Company holding tickets for its employees to claim before `claim_deadline`
+    #[derive(
+        scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, PartialEq, Eq, Copy, Clone,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct CorporateAccount {
+        /// Sum of the allowances not claimed yet
+        pub allocated: Balance,
+        pub claim_deadline: Timestamp,
     }
 
     impl TicketEvent {
-        /// Constructor that initializes a new `TicketEvent` contract.
+        /// Legacy constructor taking every parameter positionally.
+        ///
+        /// Prefer `new_with_config`.
         #[ink(constructor)]
         pub fn new(
             total_tickets: Balance,
@@ -60,46 +1209,224 @@ mod ticket_event {
             price: u32,
             ticket_ref_code_hash: Hash,
         ) -> Self {
+            Self::new_with_config(EventConfig {
+                total_tickets,
+                version,
+                name,
+                location,
+                symbol,
+                date,
+                price,
+                ticket_ref_code_hash,
+                model: TicketModel::Fungible,
+            })
+        }
+
+        /// Constructor that initializes a new `TicketEvent` contract from an `EventConfig`.
+        #[ink(constructor)]
+        pub fn new_with_config(config: EventConfig) -> Self {
+            let EventConfig {
+                total_tickets,
+                version,
+                name,
+                location,
+                symbol,
+                date,
+                price,
+                ticket_ref_code_hash,
+                model,
+            } = config;
             let caller = Self::env().caller();
-            let salt = version.to_le_bytes();
-            let ticket_ref = TicketRef::new(total_tickets)
-                .endowment(15)
-                .code_hash(ticket_ref_code_hash)
-                .salt_bytes(salt)
-                .instantiate()
+            let ticket_ref = Self::instantiate_ticket(total_tickets, version, ticket_ref_code_hash)
                 .unwrap_or_else(|error| {
                     panic!("Cannot instantiate contract: {:?}", error);
                 });
             ink_lang::utils::initialize_contract(|contract: &mut Self| {
                 //hardcoded now, should just use random number generator
                 contract.total_tickets = total_tickets;
-                contract.name = name;
-                contract.location = location;
-                contract.symbol = symbol;
-                contract.date = date;
+                Lazy::set(&mut contract.name, name);
+                Lazy::set(&mut contract.location, location);
+                Lazy::set(&mut contract.symbol, symbol);
+                Lazy::set(&mut contract.date, date);
+                Lazy::set(&mut contract.tax_jurisdiction, String::new());
                 contract.price = price;
                 contract.balance.insert(&caller, &total_tickets);
-                contract.ticket_owner.insert(&0, &caller);
-                contract.ticket_ref = ticket_ref;
+                contract.non_fungible = model == TicketModel::NonFungible;
+                if !contract.non_fungible {
+                    contract.ticket_owner.insert(&0, &caller);
+                }
+                Lazy::set(&mut contract.ticket_ref, Some(ticket_ref));
+                contract.owner = caller;
+                contract.last_admin_action = Self::env().block_timestamp();
+                contract.sale_open = true;
+            })
+        }
+
+        /// Constructor that creates the event without instantiating the `Ticket` contract.
+        ///
+        /// Metadata is left empty and the sale stays closed until the owner has
+        /// configured the event and called `open_sale`.
+        #[ink(constructor)]
+        pub fn new_unlinked(model: TicketModel) -> Self {
+            let caller = Self::env().caller();
+            ink_lang::utils::initialize_contract(|contract: &mut Self| {
+                Lazy::set(&mut contract.ticket_ref, None);
+                Lazy::set(&mut contract.name, String::new());
+                Lazy::set(&mut contract.location, String::new());
+                Lazy::set(&mut contract.symbol, String::new());
+                Lazy::set(&mut contract.date, String::new());
+                Lazy::set(&mut contract.tax_jurisdiction, String::new());
+                contract.owner = caller;
+                contract.last_admin_action = Self::env().block_timestamp();
+                contract.non_fungible = model == TicketModel::NonFungible;
             })
         }
 
+        /// Instantiates the `Ticket` contract this event is linked to
+        fn instantiate_ticket(
+            total_tickets: Balance,
+            version: u32,
+            ticket_ref_code_hash: Hash,
+        ) -> Result<TicketRef, ink_env::Error> {
+            let salt = version.to_le_bytes();
+            TicketRef::new(total_tickets)
+                .endowment(15)
+                .code_hash(ticket_ref_code_hash)
+                .salt_bytes(salt)
+                .instantiate()
+        }
+
+        /// Returns an error if the caller is not the owner of the event
+        fn ensure_owner(&mut self) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            // Any admin action shows the owner still holds their keys
+            self.last_admin_action = self.env().block_timestamp();
+            Ok(())
+        }
+
+        /// Returns an error if the sale has already been opened
+        fn ensure_sale_not_open(&self) -> Result<(), Error> {
+            if self.sale_open {
+                return Err(Error::SaleAlreadyOpen);
+            }
+            Ok(())
+        }
+
+        /// Sets name, location, symbol and date of the event
+        #[ink(message)]
+        pub fn set_event_details(
+            &mut self,
+            name: String,
+            location: String,
+            symbol: String,
+            date: String,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            Lazy::set(&mut self.name, name);
+            Lazy::set(&mut self.location, location);
+            Lazy::set(&mut self.symbol, symbol);
+            Lazy::set(&mut self.date, date);
+            Ok(())
+        }
+
+        /// Sets the price of a ticket
+        #[ink(message)]
+        pub fn set_price(&mut self, price: u32) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.price = price;
+            Ok(())
+        }
+
+        /// Sets the total amount of tickets, all held by the owner, before the sale opens
+        #[ink(message)]
+        pub fn set_total_tickets(&mut self, total_tickets: Balance) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.ensure_sale_not_open()?;
+            self.total_tickets = total_tickets;
+            if total_tickets == 0 {
+                self.balance.remove(&self.owner);
+            } else {
+                self.balance.insert(&self.owner, &total_tickets);
+            }
+            if !self.non_fungible {
+                self.ticket_owner.insert(&0, &self.owner);
+            }
+            Ok(())
+        }
+
+        /// Instantiates and links the `Ticket` contract before the sale opens
+        #[ink(message)]
+        pub fn link_ticket_contract(
+            &mut self,
+            version: u32,
+            ticket_ref_code_hash: Hash,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.ensure_sale_not_open()?;
+            if Lazy::get(&self.ticket_ref).is_some() {
+                return Err(Error::AlreadyLinked);
+            }
+            let ticket_ref =
+                Self::instantiate_ticket(self.total_tickets, version, ticket_ref_code_hash)
+                    .map_err(|_| Error::CannotInstantiate)?;
+            Lazy::set(&mut self.ticket_ref, Some(ticket_ref));
+            Ok(())
+        }
+
+        /// Returns the account of the linked `Ticket` contract, if any
+        #[ink(message)]
+        pub fn get_ticket_contract(&self) -> Option<AccountId> {
+            Lazy::get(&self.ticket_ref)
+                .as_ref()
+                .map(|ticket_ref| ticket_ref.to_account_id())
+        }
+
+        /// Opens the sale so tickets can be minted
+        #[ink(message)]
+        pub fn open_sale(&mut self) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.ensure_sale_not_open()?;
+            if self.cancelled {
+                return Err(Error::EventCancelled);
+            }
+            self.sale_open = true;
+            Ok(())
+        }
+
+        /// Returns true if the sale is open
+        #[ink(message)]
+        pub fn is_sale_open(&self) -> bool {
+            self.sale_open
+        }
+
         /// Returns the owner of the event
         #[ink(message)]
         pub fn owner(&self) -> AccountId {
-            self.env().caller()
+            self.owner
         }
 
         /// Returns the name of the event
         #[ink(message)]
         pub fn get_name(&self) -> String {
-            self.name.clone()
+            self.name().into()
+        }
+
+        /// Borrows the name of the event
+        pub fn name(&self) -> &str {
+            Lazy::get(&self.name)
         }
 
         /// Returns the location of the event
         #[ink(message)]
         pub fn get_location(&self) -> String {
-            self.location.clone()
+            self.location().into()
+        }
+
+        /// Borrows the location of the event
+        pub fn location(&self) -> &str {
+            Lazy::get(&self.location)
         }
 
         /// Returns the total amount of tickets available
@@ -117,13 +1444,23 @@ mod ticket_event {
         /// Returns the date of the event
         #[ink(message)]
         pub fn get_date(&self) -> String {
-            self.date.clone()
+            self.date().into()
+        }
+
+        /// Borrows the date of the event
+        pub fn date(&self) -> &str {
+            Lazy::get(&self.date)
         }
 
         /// Returns the symbol of the event
         #[ink(message)]
         pub fn get_symbol(&self) -> String {
-            self.symbol.clone()
+            self.symbol().into()
+        }
+
+        /// Borrows the symbol of the event
+        pub fn symbol(&self) -> &str {
+            Lazy::get(&self.symbol)
         }
 
         /// Returns the balance of the owner
@@ -139,28 +1476,67 @@ mod ticket_event {
             self.balance.get(&owner).unwrap_or(0)
         }
 
-        /// Mints new tickets
+        /// Mints new tickets to the owner, e.g. for comps and promoter allocations.
+        /// Attendees buy their tickets through `buy_ticket`.
         #[ink(message)]
         pub fn mint(&mut self, event_id: EventId, amount: Balance) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if !self.sale_open {
+                return Err(Error::SaleNotOpen);
+            }
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            self.record_activity(amount, 0)?;
             let caller = self.env().caller();
+            self.run_hook(
+                HookPoint::BeforePurchase,
+                ExecutionInput::new(Selector::new(ink_lang::selector_bytes!(
+                    "LifecycleHook::before_purchase"
+                )))
+                .push_arg(caller)
+                .push_arg(event_id)
+                .push_arg(amount),
+            )?;
 
-            for _ in 0..amount {
-                self.add_token_to(caller, event_id)?;
-                self.total_tickets += 1;
+            if self.non_fungible {
+                let ticket_ids = Self::ticket_ids(event_id, amount)?;
+                if ticket_ids.clone().any(|ticket_id| self.is_taken(ticket_id)) {
+                    return Err(Error::TokenExists);
+                }
+                for ticket_id in ticket_ids {
+                    self.ticket_owner.insert(&ticket_id, &caller);
+                    self.index_ticket(ticket_id);
+                }
+            } else {
+                self.ticket_owner.insert(&event_id, &caller);
             }
+            let balance = self.balance.get(&caller).unwrap_or(0);
+            self.balance.insert(&caller, &(balance + amount));
+            self.total_tickets += amount;
             Ok(())
         }
 
-        /// Adds the token id to the AccountId
+        /// Allows or disallows the operator to move all of the caller's tickets
         #[ink(message)]
-        pub fn add_token_to(&mut self, to: AccountId, event_id: EventId) -> Result<(), Error> {
-            let balance = self.balance.get(&to).unwrap_or(0);
-            self.balance.insert(&to, &(balance + 1));
-            self.ticket_owner.insert(&event_id, &to);
-            Ok(())
+        pub fn set_approval_for_all(&mut self, operator: AccountId, approved: bool) {
+            let caller = self.env().caller();
+            if approved {
+                self.operators.insert(&(caller, operator), &true);
+            } else {
+                self.operators.remove(&(caller, operator));
+            }
+        }
+
+        /// Returns true if the operator may move all of the holder's tickets
+        #[ink(message)]
+        pub fn is_approved_for_all(&self, holder: AccountId, operator: AccountId) -> bool {
+            self.operators.get(&(holder, operator)).unwrap_or(false)
         }
 
-        /// Transfers token id from the sender to the accountID
+        /// Transfers token id from the sender to the accountID.
+        ///
+        /// The caller must be `from` or an operator approved by `from`.
         #[ink(message)]
         pub fn transfer_from(
             &mut self,
@@ -168,28 +1544,97 @@ mod ticket_event {
             to: AccountId,
             event_id: EventId,
             tickets: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != from && !self.is_approved_for_all(from, caller) {
+                return Err(Error::NotApproved);
+            }
+            self.ensure_unallocated(from, tickets)?;
+            self.transfer(from, to, event_id, tickets)
+        }
+
+        /// Moves the tickets without checking corporate allowances
+        fn transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            event_id: EventId,
+            tickets: Balance,
         ) -> Result<(), Error> {
             // let caller = self.env().caller();
             if !self.exists(event_id) {
                 return Err(Error::TokenNotFound);
             }
+            // Refunds are owed to the holders as they were at the cancellation
+            if self.cancelled {
+                return Err(Error::EventCancelled);
+            }
+            self.record_activity(0, tickets)?;
 
-            for _ in 0..tickets {
-                self.remove_token_from(from, event_id)?;
-                self.add_token_to(to, event_id)?;
+            if self.non_fungible {
+                for ticket_id in Self::ticket_ids(event_id, tickets)? {
+                    if self.ticket_owner.get(&ticket_id) != Some(from) {
+                        return Err(Error::NotOwner);
+                    }
+                    self.ensure_unused(ticket_id)?;
+                    self.ensure_unlocked(ticket_id)?;
+                }
+            }
+            // The hook is asked about every ticket ID moved, fungible tickets share one
+            let hooked = if self.non_fungible { tickets } else { 1 };
+            for ticket_id in Self::ticket_ids(event_id, hooked)? {
+                self.run_hook(
+                    HookPoint::BeforeTransfer,
+                    ExecutionInput::new(Selector::new(ink_lang::selector_bytes!(
+                        "LifecycleHook::before_transfer"
+                    )))
+                    .push_arg(from)
+                    .push_arg(to)
+                    .push_arg(ticket_id),
+                )?;
+            }
+            let collected = if self.non_fungible {
+                Self::ticket_ids(event_id, tickets)?
+                    .map(|ticket_id| self.ticket_payments.get(&ticket_id).unwrap_or(0))
+                    .sum()
+            } else {
+                // Fungible tickets carry an even share of what the holder paid for them
+                let held = self.balance.get(&from).unwrap_or(0);
+                (self.payment_of(from) * tickets)
+                    .checked_div(held)
+                    .unwrap_or(0)
+            };
+            self.move_balance(from, to, tickets)?;
+            self.reduce_payment(from, collected);
+            self.record_payment(to, collected);
+            if self.non_fungible {
+                for ticket_id in Self::ticket_ids(event_id, tickets)? {
+                    self.ticket_owner.insert(&ticket_id, &to);
+                    // Listings and offers of the previous holder can't be taken up anymore
+                    #[cfg(feature = "resale")]
+                    self.listings.remove(&ticket_id);
+                    self.pending_transfers.remove(&ticket_id);
+                    self.log_change(ChangeKind::Transfer, ticket_id, to);
+                }
+            } else {
+                self.ticket_owner.insert(&event_id, &to);
             }
             Ok(())
         }
 
         /// Removes token id from the owner
-        #[ink(message)]
-        pub fn remove_token_from(
-            &mut self,
-            from: AccountId,
-            event_id: EventId,
-        ) -> Result<(), Error> {
-            let balance = self.balance.get(&from).unwrap_or(0);
-            self.balance.insert(&from, &(balance - 1));
+        fn remove_token_from(&mut self, from: AccountId, event_id: EventId) -> Result<(), Error> {
+            let balance = self
+                .balance
+                .get(&from)
+                .unwrap_or(0)
+                .checked_sub(1)
+                .ok_or(Error::InsufficientBalance)?;
+            if balance == 0 {
+                self.balance.remove(&from);
+            } else {
+                self.balance.insert(&from, &balance);
+            }
             self.ticket_owner.remove(event_id);
             Ok(())
         }
@@ -205,7 +1650,3673 @@ mod ticket_event {
         pub fn get_bool(&self) -> bool {
             true
         }
-    }
+
+        /// Returns the refund owed to the account
+        #[ink(message)]
+        pub fn refund_of(&self, account: AccountId) -> Balance {
+            self.refunds.get(&account).unwrap_or(0)
+        }
+
+        /// Returns the deadline for claiming refunds, if set
+        #[ink(message)]
+        pub fn get_claims_deadline(&self) -> Option<Timestamp> {
+            self.claims_deadline
+        }
+
+        /// Pays out the refund owed to the caller
+        #[ink(message)]
+        pub fn claim_refund(&mut self) -> Result<Balance, Error> {
+            if self.refunds_swept || self.claims_deadline_passed() {
+                return Err(Error::ClaimsClosed);
+            }
+            let caller = self.env().caller();
+            let amount = self.refunds.get(&caller).unwrap_or(0);
+            if amount == 0 {
+                return Err(Error::NothingToClaim);
+            }
+            self.run_hook(
+                HookPoint::BeforeRefund,
+                ExecutionInput::new(Selector::new(ink_lang::selector_bytes!(
+                    "LifecycleHook::before_refund"
+                )))
+                .push_arg(caller)
+                .push_arg(amount),
+            )?;
+            self.env()
+                .transfer(caller, amount)
+                .map_err(|_| Error::TransferFailed)?;
+            self.refunds.remove(&caller);
+            self.total_refunds_owed -= amount;
+            Ok(amount)
+        }
+
+        /// Sets the deadline after which unclaimed refunds can be swept.
+        ///
+        /// The deadline must be at least `MIN_CLAIMS_PERIOD` after now, the start of the event
+        /// and the latest refund credit. Later credits push it back by the same period.
+        #[ink(message)]
+        pub fn set_claims_deadline(&mut self, deadline: Timestamp) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if self.refunds_swept {
+                return Err(Error::ClaimsClosed);
+            }
+            let earliest = self
+                .env()
+                .block_timestamp()
+                .max(self.event_time.unwrap_or(0))
+                .max(self.last_credit_at)
+                .saturating_add(MIN_CLAIMS_PERIOD);
+            if deadline < earliest {
+                return Err(Error::DeadlineTooSoon);
+            }
+            self.claims_deadline = Some(deadline);
+            Ok(())
+        }
+
+        /// Transfers all unclaimed refunds to the owner once the claims deadline has passed
+        #[ink(message)]
+        pub fn sweep_unclaimed(&mut self) -> Result<Balance, Error> {
+            self.ensure_owner()?;
+            if self.refunds_swept {
+                return Err(Error::ClaimsClosed);
+            }
+            if !self.claims_deadline_passed() {
+                return Err(Error::ClaimsStillOpen);
+            }
+            let amount = self.total_refunds_owed;
+            self.env()
+                .transfer(self.owner, amount)
+                .map_err(|_| Error::TransferFailed)?;
+            self.total_refunds_owed = 0;
+            self.refunds_swept = true;
+            self.env().emit_event(UnclaimedRefundsSwept {
+                to: self.owner,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Credits a refund to the pull-payment ledger of the account.
+        ///
+        /// Once the ledger has been swept nothing can be claimed from it anymore,
+        /// so the refund is transferred to the account straight away instead.
+        fn credit_refund(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Ok(());
+            }
+            if self.refunds_swept {
+                return self
+                    .env()
+                    .transfer(account, amount)
+                    .map_err(|_| Error::TransferFailed);
+            }
+            let owed = self.refunds.get(&account).unwrap_or(0);
+            if owed == 0 {
+                self.refund_accounts
+                    .insert(&self.refund_account_count, &account);
+                self.refund_account_count += 1;
+            }
+            self.refunds.insert(&account, &(owed + amount));
+            self.total_refunds_owed += amount;
+            self.last_credit_at = self.env().block_timestamp();
+            Ok(())
+        }
+
+        /// Removes up to `limit` refund ledger entries left behind by `sweep_unclaimed`.
+        ///
+        /// Returns the number of entries processed; zero means there is nothing left to clean.
+        /// Other emptied entries, e.g. zero balances and allowances, are removed as they empty.
+        #[ink(message)]
+        pub fn cleanup_refund_ledger(&mut self, limit: u32) -> Result<u32, Error> {
+            if !self.refunds_swept {
+                return Err(Error::ClaimsStillOpen);
+            }
+            let end = self
+                .refund_account_count
+                .min(self.cleanup_cursor.saturating_add(limit));
+            for index in self.cleanup_cursor..end {
+                if let Some(account) = self.refund_accounts.get(&index) {
+                    self.refunds.remove(&account);
+                    self.refund_accounts.remove(&index);
+                }
+            }
+            let processed = end - self.cleanup_cursor;
+            self.cleanup_cursor = end;
+            Ok(processed)
+        }
+
+        /// Returns true if a claims deadline is set and has passed
+        fn claims_deadline_passed(&self) -> bool {
+            match self.claims_deadline {
+                Some(deadline) => {
+                    let latest = self.last_credit_at.saturating_add(MIN_CLAIMS_PERIOD);
+                    self.env().block_timestamp() >= deadline.max(latest)
+                }
+                None => false,
+            }
+        }
+
+        /// Sets the start of the event
+        #[ink(message)]
+        pub fn set_event_time(&mut self, event_time: Timestamp) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.event_time = Some(event_time);
+            Ok(())
+        }
+
+        /// Returns the start of the event, if set
+        #[ink(message)]
+        pub fn get_event_time(&self) -> Option<Timestamp> {
+            self.event_time
+        }
+
+        /// Binds a hash of the attendee data to the ticket ID owned by the caller
+        #[ink(message)]
+        pub fn set_attendee_data(&mut self, ticket_id: EventId, data: Hash) -> Result<(), Error> {
+            self.ensure_ticket_owner(ticket_id)?;
+            if !self.attendee_data.contains(&ticket_id) {
+                self.attendee_ids
+                    .insert(&self.attendee_id_count, &ticket_id);
+                self.attendee_id_count += 1;
+            }
+            self.attendee_data.insert(&ticket_id, &data);
+            Ok(())
+        }
+
+        /// Returns the hash of the attendee data bound to the ticket ID
+        #[ink(message)]
+        pub fn get_attendee_data(&self, ticket_id: EventId) -> Option<Hash> {
+            self.attendee_data.get(&ticket_id)
+        }
+
+        /// Removes the attendee data bound to the ticket ID owned by the caller
+        #[ink(message)]
+        pub fn clear_attendee_data(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            self.ensure_ticket_owner(ticket_id)?;
+            self.attendee_data.remove(&ticket_id);
+            Ok(())
+        }
+
+        /// Removes attendee data for up to `limit` ticket IDs once the event has started.
+        ///
+        /// Returns the number of entries processed; zero means there is nothing left to purge.
+        #[ink(message)]
+        pub fn purge_attendee_data(&mut self, limit: u32) -> Result<u32, Error> {
+            self.ensure_owner()?;
+            self.ensure_event_started()?;
+            let end = self
+                .attendee_id_count
+                .min(self.purge_cursor.saturating_add(limit));
+            for index in self.purge_cursor..end {
+                if let Some(ticket_id) = self.attendee_ids.get(&index) {
+                    self.attendee_data.remove(&ticket_id);
+                    self.attendee_ids.remove(&index);
+                }
+            }
+            let processed = end - self.purge_cursor;
+            self.purge_cursor = end;
+            Ok(processed)
+        }
+
+        /// Returns an error if the start of the event is not set or has not been reached
+        fn ensure_event_started(&self) -> Result<(), Error> {
+            match self.event_time {
+                Some(event_time) if self.env().block_timestamp() >= event_time => Ok(()),
+                _ => Err(Error::EventNotOver),
+            }
+        }
+
+        /// Returns an error if the caller does not own the ticket ID
+        fn ensure_ticket_owner(&self, ticket_id: EventId) -> Result<(), Error> {
+            match self.ticket_owner.get(&ticket_id) {
+                Some(owner) if owner == self.env().caller() => Ok(()),
+                Some(_) => Err(Error::NotOwner),
+                None => Err(Error::TokenNotFound),
+            }
+        }
+
+        /// Registers an extension contract for the lifecycle point, replacing any previous one
+        #[ink(message)]
+        pub fn register_hook(
+            &mut self,
+            point: HookPoint,
+            contract: AccountId,
+            gas_limit: u64,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if gas_limit == 0 || gas_limit > MAX_HOOK_GAS {
+                return Err(Error::HookGasTooHigh);
+            }
+            self.hooks.insert(
+                &point,
+                &Hook {
+                    contract,
+                    gas_limit,
+                },
+            );
+            Ok(())
+        }
+
+        /// Removes the extension contract registered for the lifecycle point
+        #[ink(message)]
+        pub fn unregister_hook(&mut self, point: HookPoint) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.hooks.remove(&point);
+            Ok(())
+        }
+
+        /// Returns the extension contract registered for the lifecycle point, if any
+        #[ink(message)]
+        pub fn get_hook(&self, point: HookPoint) -> Option<Hook> {
+            self.hooks.get(&point)
+        }
+
+        /// Calls the hook registered for the lifecycle point, if any, within its gas budget
+        fn run_hook<Args>(&self, point: HookPoint, input: ExecutionInput<Args>) -> Result<(), Error>
+        where
+            Args: scale::Encode,
+        {
+            let hook = match self.hooks.get(&point) {
+                Some(hook) => hook,
+                None => return Ok(()),
+            };
+            let approved = build_call::<ink_env::DefaultEnvironment>()
+                .call_type(Call::new().callee(hook.contract).gas_limit(hook.gas_limit))
+                .exec_input(input)
+                .returns::<bool>()
+                .fire()
+                .map_err(|_| Error::HookRejected)?;
+            if !approved {
+                return Err(Error::HookRejected);
+            }
+            Ok(())
+        }
+
+        /// Sets the per-block thresholds of the circuit breaker, 0 disables a threshold
+        #[ink(message)]
+        pub fn set_circuit_breaker(
+            &mut self,
+            max_purchases_per_block: Balance,
+            max_transfers_per_block: Balance,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.max_purchases_per_block = max_purchases_per_block;
+            self.max_transfers_per_block = max_transfers_per_block;
+            Ok(())
+        }
+
+        /// Unpauses minting and transfers after the circuit breaker tripped
+        #[ink(message)]
+        pub fn reset_circuit_breaker(&mut self) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.paused = false;
+            self.block_purchases = 0;
+            self.block_transfers = 0;
+            Ok(())
+        }
+
+        /// Returns true if minting and transfers are paused
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Counts the activity of the current block and trips the circuit breaker
+        /// when a threshold is exceeded.
+        ///
+        /// The action that trips the breaker still completes, since an error would revert the
+        /// pause along with it. Every later mint, purchase or transfer is rejected until reset.
+        fn record_activity(&mut self, purchases: Balance, transfers: Balance) -> Result<(), Error> {
+            if self.paused {
+                return Err(Error::Paused);
+            }
+            let block = self.env().block_number();
+            if block != self.activity_block {
+                self.activity_block = block;
+                self.block_purchases = 0;
+                self.block_transfers = 0;
+            }
+            self.block_purchases = self.block_purchases.saturating_add(purchases);
+            self.block_transfers = self.block_transfers.saturating_add(transfers);
+            let exceeded = |count: Balance, max: Balance| max != 0 && count > max;
+            if exceeded(self.block_purchases, self.max_purchases_per_block)
+                || exceeded(self.block_transfers, self.max_transfers_per_block)
+            {
+                self.paused = true;
+                self.env().emit_event(CircuitBreakerTripped {
+                    block,
+                    purchases: self.block_purchases,
+                    transfers: self.block_transfers,
+                });
+            }
+            Ok(())
+        }
+
+        /// Sets or removes the contract used to price tickets
+        #[ink(message)]
+        pub fn set_pricing_strategy(&mut self, strategy: Option<AccountId>) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.pricing_strategy = strategy;
+            Ok(())
+        }
+
+        /// Returns the contract used to price tickets, if set
+        #[ink(message)]
+        pub fn get_pricing_strategy(&self) -> Option<AccountId> {
+            self.pricing_strategy
+        }
+
+        /// Returns the amount of tickets sold
+        #[ink(message)]
+        pub fn get_tickets_sold(&self) -> Balance {
+            self.tickets_sold
+        }
+
+        /// Buys tickets of the ticket ID from the owner's inventory.
+        ///
+        /// The transferred value must match the total price exactly.
+        #[ink(message, payable)]
+        pub fn buy_ticket(&mut self, event_id: EventId, quantity: Balance) -> Result<(), Error> {
+            self.purchase(event_id, quantity, PurchaseOptions::default())
+        }
+
+        /// Buys tickets of the ticket ID with the extras selected in `options`.
+        ///
+        /// The transferred value must match `get_quote_with_options` exactly.
+        #[ink(message, payable)]
+        pub fn buy_ticket_with_options(
+            &mut self,
+            event_id: EventId,
+            quantity: Balance,
+            options: PurchaseOptions,
+        ) -> Result<(), Error> {
+            self.purchase(event_id, quantity, options)
+        }
+
+        /// Buys tickets of the ticket ID in a concession category at its own price.
+        ///
+        /// The transferred value must match the total price exactly.
+        #[ink(message, payable)]
+        pub fn buy_concession_ticket(
+            &mut self,
+            event_id: EventId,
+            quantity: Balance,
+            concession: Concession,
+        ) -> Result<(), Error> {
+            self.purchase(
+                event_id,
+                quantity,
+                PurchaseOptions {
+                    concession: Some(concession),
+                    ..PurchaseOptions::default()
+                },
+            )
+        }
+
+        /// Sets the price and cap of a concession category, keeping its sales
+        #[ink(message)]
+        pub fn set_concession(
+            &mut self,
+            concession: Concession,
+            price: Balance,
+            cap: Balance,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            let sold = self
+                .concessions
+                .get(&concession)
+                .map_or(0, |terms| terms.sold);
+            self.concessions
+                .insert(&concession, &ConcessionTerms { price, cap, sold });
+            Ok(())
+        }
+
+        /// Returns the terms and sales of a concession category, if offered
+        #[ink(message)]
+        pub fn get_concession(&self, concession: Concession) -> Option<ConcessionTerms> {
+            self.concessions.get(&concession)
+        }
+
+        /// Returns the concession category of the ticket ID, if any
+        #[ink(message)]
+        pub fn concession_of(&self, ticket_id: EventId) -> Option<Concession> {
+            self.ticket_concession.get(&ticket_id)
+        }
+
+        /// Buys tickets of the ticket ID from the owner's inventory
+        fn purchase(
+            &mut self,
+            event_id: EventId,
+            quantity: Balance,
+            options: PurchaseOptions,
+        ) -> Result<(), Error> {
+            if self.queue_mode {
+                return Err(Error::QueueOnly);
+            }
+            self.purchase_for(
+                self.env().caller(),
+                event_id,
+                quantity,
+                options,
+                self.env().transferred_value(),
+            )
+        }
+
+        /// Sells tickets of the ticket ID from the owner's inventory to the buyer,
+        /// `payment` must match the charges exactly
+        fn purchase_for(
+            &mut self,
+            buyer: AccountId,
+            event_id: EventId,
+            quantity: Balance,
+            options: PurchaseOptions,
+            payment: Balance,
+        ) -> Result<(), Error> {
+            if !self.sale_open {
+                return Err(Error::SaleNotOpen);
+            }
+            if self.paused {
+                return Err(Error::Paused);
+            }
+            self.ensure_throttle(quantity)?;
+            self.run_hook(
+                HookPoint::BeforePurchase,
+                ExecutionInput::new(Selector::new(ink_lang::selector_bytes!(
+                    "LifecycleHook::before_purchase"
+                )))
+                .push_arg(buyer)
+                .push_arg(event_id)
+                .push_arg(quantity),
+            )?;
+
+            let inventory = self.balance.get(&self.owner).unwrap_or(0);
+            if quantity == 0 || inventory.saturating_sub(self.auctioned_tickets) < quantity {
+                return Err(Error::SoldOut);
+            }
+            if self.non_fungible
+                && Self::ticket_ids(event_id, quantity)?.any(|ticket_id| self.is_taken(ticket_id))
+            {
+                return Err(Error::TokenExists);
+            }
+            let charges = self.charges(event_id, quantity, options)?;
+            if payment != charges.total() {
+                return Err(Error::IncorrectPayment);
+            }
+            // Only purchases that go through count towards the throttle and circuit breaker
+            self.record_activity(quantity, 0)?;
+            let Charges {
+                price,
+                tax,
+                carbon_offset,
+                ..
+            } = charges;
+            let concession = options.concession;
+            let face_value = price / quantity;
+
+            if let Some(concession) = concession {
+                if let Some(mut terms) = self.concessions.get(&concession) {
+                    terms.sold += quantity;
+                    self.concessions.insert(&concession, &terms);
+                }
+            }
+
+            let collected = price + tax + carbon_offset;
+            self.move_balance(self.owner, buyer, quantity)?;
+            if self.non_fungible {
+                let mut remainder = collected % quantity;
+                for ticket_id in Self::ticket_ids(event_id, quantity)? {
+                    self.ticket_owner.insert(&ticket_id, &buyer);
+                    self.index_ticket(ticket_id);
+                    self.face_values.insert(&ticket_id, &face_value);
+                    self.ticket_payments
+                        .insert(&ticket_id, &(collected / quantity + remainder));
+                    remainder = 0;
+                    self.original_buyers.insert(&ticket_id, &buyer);
+                    if let Some(concession) = concession {
+                        self.ticket_concession.insert(&ticket_id, &concession);
+                    }
+                }
+            } else {
+                self.ticket_owner.insert(&event_id, &buyer);
+            }
+            self.tickets_sold += quantity;
+            self.revenue += price;
+            self.record_payment(buyer, collected);
+            if let Some(policy) = self.unused_refund_policy {
+                self.unused_refund_reserve +=
+                    price * Balance::from(policy.reserve_bps) / Balance::from(MAX_BPS);
+            }
+            self.carbon_offset_collected += carbon_offset;
+            self.carbon_offset_bucket += carbon_offset;
+            if options.vouchers > 0 {
+                self.mint_vouchers(buyer, options.vouchers);
+            }
+            if options.parking > 0 {
+                self.issue_parking(buyer, options.parking);
+            }
+            self.record_extra_payment(
+                buyer,
+                charges.vouchers + charges.parking + charges.extras_tax,
+            );
+            if options.credit > 0 {
+                self.add_credit(buyer, options.credit);
+            }
+            let receipt = Receipt {
+                concession,
+                carbon_offset,
+                vouchers: options.vouchers,
+                parking: options.parking,
+                credit: options.credit,
+                ..self.receipt(
+                    buyer,
+                    event_id,
+                    quantity,
+                    price + charges.vouchers + charges.parking,
+                    tax + charges.extras_tax,
+                )
+            };
+            self.invoice(receipt);
+            Ok(())
+        }
+
+        /// Returns the receipt of a sale of `quantity` tickets of the ticket ID with no extras
+        fn receipt(
+            &self,
+            buyer: AccountId,
+            ticket_id: EventId,
+            quantity: Balance,
+            price: Balance,
+            tax: Balance,
+        ) -> Receipt {
+            Receipt {
+                buyer,
+                ticket_id,
+                quantity,
+                price,
+                tax,
+                concession: None,
+                carbon_offset: 0,
+                vouchers: 0,
+                parking: 0,
+                credit: 0,
+                add_on: None,
+                upgrade: None,
+                timestamp: self.env().block_timestamp(),
+            }
+        }
+
+        /// Issues the next invoice number for a completed sale, storing its receipt and booking
+        /// its tax. Every sale goes through here so the invoice numbers have no gaps.
+        fn invoice(&mut self, receipt: Receipt) {
+            self.tax_collected += receipt.tax;
+            self.invoice_count += 1;
+            let invoice = self.invoice_count;
+            self.receipts.insert(&invoice, &receipt);
+            self.env().emit_event(Purchased {
+                buyer: receipt.buyer,
+                ticket_id: receipt.ticket_id,
+                quantity: receipt.quantity,
+                price: receipt.price,
+                tax: receipt.tax,
+                invoice,
+            });
+        }
+
+        /// Sets or removes the automatic last-minute discount
+        #[ink(message)]
+        pub fn set_last_minute_discount(
+            &mut self,
+            discount: Option<LastMinuteDiscount>,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if let Some(discount) = discount {
+                if discount.sell_through_target_bps > MAX_BPS || discount.discount_bps > MAX_BPS {
+                    return Err(Error::InvalidBasisPoints);
+                }
+            }
+            self.last_minute_discount = discount;
+            Ok(())
+        }
+
+        /// Returns the automatic last-minute discount, if set
+        #[ink(message)]
+        pub fn get_last_minute_discount(&self) -> Option<LastMinuteDiscount> {
+            self.last_minute_discount
+        }
+
+        /// Returns the discount in basis points that currently applies, 0 if none
+        #[ink(message)]
+        pub fn active_discount_bps(&self) -> u16 {
+            let (discount, event_time) = match (self.last_minute_discount, self.event_time) {
+                (Some(discount), Some(event_time)) => (discount, event_time),
+                _ => return 0,
+            };
+            let now = self.env().block_timestamp();
+            if now >= event_time || event_time - now > discount.window {
+                return 0;
+            }
+            let sell_through_bps = match self.total_tickets {
+                0 => Balance::from(MAX_BPS),
+                total => self.tickets_sold * Balance::from(MAX_BPS) / total,
+            };
+            if sell_through_bps >= Balance::from(discount.sell_through_target_bps) {
+                return 0;
+            }
+            discount.discount_bps
+        }
+
+        /// Returns the receipt of the purchase with the invoice number, if any
+        #[ink(message)]
+        pub fn get_receipt(&self, invoice: InvoiceNumber) -> Option<Receipt> {
+            self.receipts.get(&invoice)
+        }
+
+        /// Returns the invoice number of the latest purchase, 0 before the first purchase
+        #[ink(message)]
+        pub fn get_invoice_count(&self) -> InvoiceNumber {
+            self.invoice_count
+        }
+
+        /// Returns the revenue realized so far and the revenue of selling the remaining
+        /// inventory at the current ticket price, including any active discount.
+        ///
+        /// Per-ticket price overrides and the pricing strategy are not taken into account
+        /// for the remaining inventory.
+        #[ink(message)]
+        pub fn projected_revenue(&self) -> (Balance, Balance) {
+            let inventory = self.balance.get(&self.owner).unwrap_or(0);
+            let remaining = Balance::from(self.price).saturating_mul(inventory);
+            let discount =
+                remaining * Balance::from(self.active_discount_bps()) / Balance::from(MAX_BPS);
+            (self.revenue, remaining - discount)
+        }
+
+        /// Returns the part of the contract balance the owner may withdraw, i.e. everything
+        /// not reserved by `reserved_funds`
+        #[ink(message)]
+        pub fn withdrawable_proceeds(&self) -> Balance {
+            self.env().balance().saturating_sub(self.reserved_funds())
+        }
+
+        /// Returns the funds the contract holds for others: refunds owed, the carbon offset
+        /// bucket, outstanding credit, the unused-ticket refund reserve and escrow
+        fn reserved_funds(&self) -> Balance {
+            self.total_refunds_owed
+                .saturating_add(self.carbon_offset_bucket)
+                .saturating_add(self.credits_outstanding)
+                .saturating_add(self.unused_refund_reserve)
+                .saturating_add(self.escrowed_total)
+        }
+
+        /// Transfers `amount` of the sale proceeds to the owner once the event has started,
+        /// unless it was cancelled
+        #[ink(message)]
+        pub fn withdraw_proceeds(&mut self, amount: Balance) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if self.cancelled {
+                return Err(Error::EventCancelled);
+            }
+            self.ensure_event_started()?;
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            if amount > self.withdrawable_proceeds() {
+                return Err(Error::InsufficientBalance);
+            }
+            self.env()
+                .transfer(self.owner, amount)
+                .map_err(|_| Error::TransferFailed)?;
+            Ok(())
+        }
+
+        /// Returns the exact amount `buy_ticket` would charge for `quantity` tickets of
+        /// the ticket ID right now
+        #[ink(message)]
+        pub fn get_quote(&self, event_id: EventId, quantity: Balance) -> Result<Balance, Error> {
+            self.get_quote_with_options(event_id, quantity, PurchaseOptions::default())
+        }
+
+        /// Returns the exact amount `buy_ticket_with_options` would charge for `quantity`
+        /// tickets of the ticket ID with the extras selected in `options` right now
+        #[ink(message)]
+        pub fn get_quote_with_options(
+            &self,
+            event_id: EventId,
+            quantity: Balance,
+            options: PurchaseOptions,
+        ) -> Result<Balance, Error> {
+            Ok(self.charges(event_id, quantity, options)?.total())
+        }
+
+        /// Returns what buying `quantity` tickets of the ticket ID with `options` costs
+        fn charges(
+            &self,
+            event_id: EventId,
+            quantity: Balance,
+            options: PurchaseOptions,
+        ) -> Result<Charges, Error> {
+            let price = match options.concession {
+                Some(concession) => {
+                    let terms = self
+                        .concessions
+                        .get(&concession)
+                        .ok_or(Error::ConcessionNotOffered)?;
+                    if terms.sold + quantity > terms.cap {
+                        return Err(Error::SoldOut);
+                    }
+                    terms
+                        .price
+                        .checked_mul(quantity)
+                        .ok_or(Error::PricingFailed)?
+                }
+                None => self.total_price(event_id, quantity)?,
+            };
+            let carbon_offset = if options.carbon_offset {
+                if self.carbon_offset_partner.is_none() {
+                    return Err(Error::CarbonOffsetNotOffered);
+                }
+                self.carbon_offset_per_ticket
+                    .checked_mul(quantity)
+                    .ok_or(Error::PricingFailed)?
+            } else {
+                0
+            };
+            let vouchers = self.voucher_cost(options.vouchers)?;
+            let parking = self.parking_cost(options.parking)?;
+            Ok(Charges {
+                price,
+                tax: self.tax_on(price),
+                carbon_offset,
+                vouchers,
+                parking,
+                extras_tax: self.tax_on(vouchers + parking),
+                credit: options.credit,
+            })
+        }
+
+        /// Sets the tax rate charged on top of the ticket price and its jurisdiction code
+        #[ink(message)]
+        pub fn set_tax(&mut self, rate_bps: u16, jurisdiction: String) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if rate_bps > MAX_BPS {
+                return Err(Error::InvalidBasisPoints);
+            }
+            self.tax_rate_bps = rate_bps;
+            Lazy::set(&mut self.tax_jurisdiction, jurisdiction);
+            Ok(())
+        }
+
+        /// Returns the tax rate in basis points and its jurisdiction code
+        #[ink(message)]
+        pub fn get_tax(&self) -> (u16, String) {
+            (self.tax_rate_bps, self.tax_jurisdiction().into())
+        }
+
+        /// Borrows the code of the tax jurisdiction
+        pub fn tax_jurisdiction(&self) -> &str {
+            Lazy::get(&self.tax_jurisdiction)
+        }
+
+        /// Returns the total tax collected
+        #[ink(message)]
+        pub fn get_tax_collected(&self) -> Balance {
+            self.tax_collected
+        }
+
+        /// Returns the tax due on the price
+        fn tax_on(&self, price: Balance) -> Balance {
+            price * Balance::from(self.tax_rate_bps) / Balance::from(MAX_BPS)
+        }
+
+        /// Splits an amount paid including tax, such as a winning bid, into price and tax
+        #[cfg(feature = "auctions")]
+        fn split_tax(&self, amount: Balance) -> (Balance, Balance) {
+            let price = amount * Balance::from(MAX_BPS)
+                / (Balance::from(MAX_BPS) + Balance::from(self.tax_rate_bps));
+            (price, amount - price)
+        }
+
+        /// Returns the total price of `quantity` tickets of the ticket ID
+        fn total_price(&self, event_id: EventId, quantity: Balance) -> Result<Balance, Error> {
+            let price = self.base_price(event_id, quantity)?;
+            let discount =
+                price * Balance::from(self.active_discount_bps()) / Balance::from(MAX_BPS);
+            Ok(price - discount)
+        }
+
+        /// Sets or removes the explicit price of a single ticket of the ticket ID
+        #[ink(message)]
+        pub fn set_price_override(
+            &mut self,
+            ticket_id: EventId,
+            price: Option<Balance>,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            match price {
+                Some(price) => self.price_overrides.insert(&ticket_id, &price),
+                None => self.price_overrides.remove(&ticket_id),
+            }
+            Ok(())
+        }
+
+        /// Returns the explicit price of a single ticket of the ticket ID, if set
+        #[ink(message)]
+        pub fn price_override_of(&self, ticket_id: EventId) -> Option<Balance> {
+            self.price_overrides.get(&ticket_id)
+        }
+
+        /// Returns the total price of `quantity` tickets of the ticket ID before discounts
+        fn base_price(&self, event_id: EventId, quantity: Balance) -> Result<Balance, Error> {
+            if self.non_fungible {
+                // Every ticket ID is a seat of its own, so overrides apply per ticket
+                let mut overridden: Balance = 0;
+                let mut unpriced = quantity;
+                for ticket_id in Self::ticket_ids(event_id, quantity)? {
+                    if let Some(price) = self.price_overrides.get(&ticket_id) {
+                        overridden = overridden.checked_add(price).ok_or(Error::PricingFailed)?;
+                        unpriced -= 1;
+                    }
+                }
+                if unpriced == 0 {
+                    return Ok(overridden);
+                }
+                return self
+                    .tier_price(event_id, unpriced)?
+                    .checked_add(overridden)
+                    .ok_or(Error::PricingFailed);
+            }
+            if let Some(price) = self.price_overrides.get(&event_id) {
+                return price.checked_mul(quantity).ok_or(Error::PricingFailed);
+            }
+            self.tier_price(event_id, quantity)
+        }
+
+        /// Returns the total price of `quantity` tickets of the ticket ID from the pricing
+        /// strategy, or from the ticket price if none is set
+        fn tier_price(&self, event_id: EventId, quantity: Balance) -> Result<Balance, Error> {
+            match self.pricing_strategy {
+                Some(strategy) => build_call::<ink_env::DefaultEnvironment>()
+                    .call_type(Call::new().callee(strategy))
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink_lang::selector_bytes!(
+                            "PricingStrategy::price_for"
+                        )))
+                        .push_arg(event_id)
+                        .push_arg(quantity)
+                        .push_arg(self.env().block_timestamp()),
+                    )
+                    .returns::<Balance>()
+                    .fire()
+                    .map_err(|_| Error::PricingFailed),
+                None => Balance::from(self.price)
+                    .checked_mul(quantity)
+                    .ok_or(Error::PricingFailed),
+            }
+        }
+
+        /// Returns how tickets are accounted for
+        #[ink(message)]
+        pub fn ticket_model(&self) -> TicketModel {
+            if self.non_fungible {
+                TicketModel::NonFungible
+            } else {
+                TicketModel::Fungible
+            }
+        }
+
+        /// Returns the owner of the ticket ID, if any
+        #[ink(message)]
+        pub fn owner_of(&self, ticket_id: EventId) -> Option<AccountId> {
+            self.ticket_owner.get(&ticket_id)
+        }
+
+        /// Moves `amount` tickets from the balance of `from` to the balance of `to`
+        fn move_balance(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            if amount == 0 {
+                return Ok(());
+            }
+            let from_balance = self.balance.get(&from).unwrap_or(0);
+            if from_balance < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            if from_balance == amount {
+                self.balance.remove(&from);
+            } else {
+                self.balance.insert(&from, &(from_balance - amount));
+            }
+            let to_balance = self.balance.get(&to).unwrap_or(0);
+            self.balance.insert(&to, &(to_balance + amount));
+            Ok(())
+        }
+
+        /// Returns the `amount` consecutive ticket IDs starting at `event_id`
+        fn ticket_ids(
+            event_id: EventId,
+            amount: Balance,
+        ) -> Result<core::ops::Range<EventId>, Error> {
+            EventId::try_from(amount)
+                .ok()
+                .and_then(|amount| event_id.checked_add(amount))
+                .map(|end| event_id..end)
+                .ok_or(Error::InvalidAmount)
+        }
+
+        /// Sets the carbon offset charged per ticket and the partner it is withdrawn to.
+        ///
+        /// Offsets are not offered while the partner is `None`. The partner can't be
+        /// changed while offsets collected for it are waiting to be withdrawn.
+        #[ink(message)]
+        pub fn set_carbon_offset(
+            &mut self,
+            per_ticket: Balance,
+            partner: Option<AccountId>,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if partner != self.carbon_offset_partner && self.carbon_offset_bucket > 0 {
+                return Err(Error::NotAllowed);
+            }
+            self.carbon_offset_per_ticket = per_ticket;
+            self.carbon_offset_partner = partner;
+            Ok(())
+        }
+
+        /// Returns the carbon offset charged per ticket and the partner it is withdrawn to
+        #[ink(message)]
+        pub fn get_carbon_offset(&self) -> (Balance, Option<AccountId>) {
+            (self.carbon_offset_per_ticket, self.carbon_offset_partner)
+        }
+
+        /// Returns the total carbon offset paid and the part not withdrawn yet
+        #[ink(message)]
+        pub fn get_carbon_offset_totals(&self) -> (Balance, Balance) {
+            (self.carbon_offset_collected, self.carbon_offset_bucket)
+        }
+
+        /// Transfers the carbon offset bucket to the offset partner.
+        ///
+        /// Can be called by the owner or the partner.
+        #[ink(message)]
+        pub fn withdraw_carbon_offset(&mut self) -> Result<Balance, Error> {
+            let partner = self
+                .carbon_offset_partner
+                .ok_or(Error::CarbonOffsetNotOffered)?;
+            let caller = self.env().caller();
+            if caller != self.owner && caller != partner {
+                return Err(Error::NotAllowed);
+            }
+            let amount = self.carbon_offset_bucket;
+            if amount == 0 {
+                return Err(Error::NothingToClaim);
+            }
+            self.env()
+                .transfer(partner, amount)
+                .map_err(|_| Error::TransferFailed)?;
+            self.carbon_offset_bucket = 0;
+            Ok(amount)
+        }
+
+        /// Sets the price of a merch voucher and the maximum amount ever minted
+        #[ink(message)]
+        pub fn set_voucher_terms(
+            &mut self,
+            price: Balance,
+            max_supply: Balance,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.voucher_price = price;
+            self.max_voucher_supply = max_supply;
+            Ok(())
+        }
+
+        /// Returns the price of a merch voucher, the maximum supply and the amount minted
+        #[ink(message)]
+        pub fn get_voucher_terms(&self) -> (Balance, Balance, Balance) {
+            (
+                self.voucher_price,
+                self.max_voucher_supply,
+                self.voucher_supply,
+            )
+        }
+
+        /// Grants or revokes the role of the account
+        #[ink(message)]
+        pub fn set_role(
+            &mut self,
+            role: Role,
+            account: AccountId,
+            granted: bool,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if granted {
+                self.roles.insert(&(role, account), &true);
+            } else {
+                self.roles.remove(&(role, account));
+            }
+            Ok(())
+        }
+
+        /// Returns true if the account has been granted the role
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.roles.get(&(role, account)).unwrap_or(false)
+        }
+
+        /// Returns an error if the caller has not been granted the role
+        fn ensure_role(&self, role: Role) -> Result<(), Error> {
+            if !self.has_role(role, self.env().caller()) {
+                return Err(Error::NotAllowed);
+            }
+            Ok(())
+        }
+
+        /// Returns the merch vouchers held by the account
+        #[ink(message)]
+        pub fn vouchers_of(&self, account: AccountId) -> Balance {
+            self.vouchers.get(&account).unwrap_or(0)
+        }
+
+        /// Buys merch vouchers without buying tickets.
+        ///
+        /// The transferred value must match the voucher price times `amount` plus tax exactly.
+        #[ink(message, payable)]
+        pub fn buy_vouchers(&mut self, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            let price = self.voucher_cost(amount)?;
+            let tax = self.tax_on(price);
+            if self.env().transferred_value() != price + tax {
+                return Err(Error::IncorrectPayment);
+            }
+            let buyer = self.env().caller();
+            self.mint_vouchers(buyer, amount);
+            self.record_extra_payment(buyer, price + tax);
+            let receipt = Receipt {
+                vouchers: amount,
+                ..self.receipt(buyer, 0, 0, price, tax)
+            };
+            self.invoice(receipt);
+            Ok(())
+        }
+
+        /// Redeems merch vouchers held by the account, called by a merch stand
+        #[ink(message)]
+        pub fn redeem_voucher(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
+            self.ensure_role(Role::MerchStand)?;
+            let merch_stand = self.env().caller();
+            let held = self.vouchers_of(account);
+            if amount == 0 || held < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            if held == amount {
+                self.vouchers.remove(&account);
+            } else {
+                self.vouchers.insert(&account, &(held - amount));
+            }
+            self.env().emit_event(VouchersRedeemed {
+                account,
+                merch_stand,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Returns the cost of `amount` merch vouchers if that many can still be minted
+        fn voucher_cost(&self, amount: Balance) -> Result<Balance, Error> {
+            if amount == 0 {
+                return Ok(0);
+            }
+            if self.voucher_supply.saturating_add(amount) > self.max_voucher_supply {
+                return Err(Error::SoldOut);
+            }
+            self.voucher_price
+                .checked_mul(amount)
+                .ok_or(Error::PricingFailed)
+        }
+
+        /// Mints merch vouchers to the account, the supply must have been checked
+        fn mint_vouchers(&mut self, to: AccountId, amount: Balance) {
+            let held = self.vouchers_of(to);
+            self.vouchers.insert(&to, &(held + amount));
+            self.voucher_supply += amount;
+            self.env().emit_event(VouchersMinted { to, amount });
+        }
+
+        /// Sets the price of a parking pass and the amount of parking spaces
+        #[ink(message)]
+        pub fn set_parking_terms(
+            &mut self,
+            price: Balance,
+            capacity: Balance,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if capacity < self.parking_sold {
+                return Err(Error::InvalidAmount);
+            }
+            self.parking_price = price;
+            self.parking_capacity = capacity;
+            Ok(())
+        }
+
+        /// Returns the price of a parking pass, the amount of parking spaces and the amount sold
+        #[ink(message)]
+        pub fn get_parking_terms(&self) -> (Balance, Balance, Balance) {
+            (self.parking_price, self.parking_capacity, self.parking_sold)
+        }
+
+        /// Returns the unused parking passes held by the account
+        #[ink(message)]
+        pub fn parking_passes_of(&self, account: AccountId) -> Balance {
+            self.parking_passes.get(&account).unwrap_or(0)
+        }
+
+        /// Buys parking passes without buying tickets.
+        ///
+        /// The transferred value must match the parking price times `amount` plus tax exactly.
+        #[ink(message, payable)]
+        pub fn buy_parking(&mut self, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            let price = self.parking_cost(amount)?;
+            let tax = self.tax_on(price);
+            if self.env().transferred_value() != price + tax {
+                return Err(Error::IncorrectPayment);
+            }
+            let buyer = self.env().caller();
+            self.issue_parking(buyer, amount);
+            self.record_extra_payment(buyer, price + tax);
+            let receipt = Receipt {
+                parking: amount,
+                ..self.receipt(buyer, 0, 0, price, tax)
+            };
+            self.invoice(receipt);
+            Ok(())
+        }
+
+        /// Uses one parking pass of the account at the vehicle gate, called by a parking scanner
+        #[ink(message)]
+        pub fn check_in_vehicle(&mut self, account: AccountId) -> Result<(), Error> {
+            self.ensure_role(Role::ParkingScanner)?;
+            let passes = self.parking_passes_of(account);
+            if passes == 0 {
+                return Err(Error::InsufficientBalance);
+            }
+            if passes == 1 {
+                self.parking_passes.remove(&account);
+            } else {
+                self.parking_passes.insert(&account, &(passes - 1));
+            }
+            self.env().emit_event(VehicleCheckedIn {
+                account,
+                scanner: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Returns the cost of `amount` parking passes if that many spaces are left
+        fn parking_cost(&self, amount: Balance) -> Result<Balance, Error> {
+            if amount == 0 {
+                return Ok(0);
+            }
+            if self.parking_sold.saturating_add(amount) > self.parking_capacity {
+                return Err(Error::SoldOut);
+            }
+            self.parking_price
+                .checked_mul(amount)
+                .ok_or(Error::PricingFailed)
+        }
+
+        /// Issues parking passes to the account, the capacity must have been checked
+        fn issue_parking(&mut self, to: AccountId, amount: Balance) {
+            let passes = self.parking_passes_of(to);
+            self.parking_passes.insert(&to, &(passes + amount));
+            self.parking_sold += amount;
+        }
+
+        /// Returns the food and drink credit of the account
+        #[ink(message)]
+        pub fn credit_of(&self, account: AccountId) -> Balance {
+            self.credits.get(&account).unwrap_or(0)
+        }
+
+        /// Returns the food and drink credit outstanding and the credit spent at vendors
+        #[ink(message)]
+        pub fn get_credit_totals(&self) -> (Balance, Balance) {
+            (self.credits_outstanding, self.credits_spent)
+        }
+
+        /// Tops up the food and drink credit of the caller with the transferred value
+        #[ink(message, payable)]
+        pub fn top_up_credit(&mut self) -> Result<(), Error> {
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            self.add_credit(self.env().caller(), amount);
+            Ok(())
+        }
+
+        /// Spends food and drink credit of the account, called by a vendor
+        #[ink(message)]
+        pub fn spend_credit(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
+            self.ensure_role(Role::Vendor)?;
+            self.take_credit(account, amount)?;
+            self.credits_spent += amount;
+            self.env().emit_event(CreditSpent {
+                account,
+                vendor: self.env().caller(),
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Moves the unspent food and drink credit of the caller to the refund ledger
+        /// once the event has started
+        #[ink(message)]
+        pub fn refund_unspent_credit(&mut self) -> Result<Balance, Error> {
+            self.ensure_event_started()?;
+            if self.refunds_swept {
+                return Err(Error::ClaimsClosed);
+            }
+            let caller = self.env().caller();
+            let amount = self.credit_of(caller);
+            if amount == 0 {
+                return Err(Error::NothingToClaim);
+            }
+            self.take_credit(caller, amount)?;
+            self.credit_refund(caller, amount)?;
+            Ok(amount)
+        }
+
+        /// Adds food and drink credit to the account
+        fn add_credit(&mut self, to: AccountId, amount: Balance) {
+            self.track_payer(to);
+            let credit = self.credit_of(to);
+            self.credits.insert(&to, &(credit + amount));
+            self.credits_outstanding += amount;
+        }
+
+        /// Removes food and drink credit from the account
+        fn take_credit(&mut self, from: AccountId, amount: Balance) -> Result<(), Error> {
+            let credit = self.credit_of(from);
+            if amount == 0 || credit < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            if credit == amount {
+                self.credits.remove(&from);
+            } else {
+                self.credits.insert(&from, &(credit - amount));
+            }
+            self.credits_outstanding -= amount;
+            Ok(())
+        }
+
+        /// Sets the days of the event the ticket ID is valid for as a bitmap,
+        /// bit `n` standing for day `n` counted from the start of the event
+        #[ink(message)]
+        pub fn set_pass_days(&mut self, ticket_id: EventId, days: u32) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            if days == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            self.pass_days.insert(&ticket_id, &days);
+            Ok(())
+        }
+
+        /// Returns the days of the event the ticket ID is valid for as a bitmap
+        #[ink(message)]
+        pub fn pass_days_of(&self, ticket_id: EventId) -> u32 {
+            self.pass_days.get(&ticket_id).unwrap_or(1)
+        }
+
+        /// Returns the days of the event the ticket ID can still be checked in on as a bitmap
+        #[ink(message)]
+        pub fn remaining_days_of(&self, ticket_id: EventId) -> u32 {
+            self.pass_days_of(ticket_id) & !self.checked_in_days.get(&ticket_id).unwrap_or(0)
+        }
+
+        /// Checks in the ticket ID for the current day of the event, called by a gate scanner.
+        ///
+        /// A ticket ID checked out earlier the same day re-enters instead of counting as
+        /// a duplicate scan.
+        #[ink(message)]
+        pub fn check_in(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            self.ensure_role(Role::GateScanner)?;
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            if self.cancelled {
+                return Err(Error::EventCancelled);
+            }
+            if !self.exists(ticket_id) {
+                return Err(Error::TokenNotFound);
+            }
+            let day = self.current_day().ok_or(Error::NotValidToday)?;
+            let bit = 1u32 << day;
+            if self.pass_days_of(ticket_id) & bit == 0 {
+                return Err(Error::NotValidToday);
+            }
+            let checked_in = self.checked_in_days.get(&ticket_id).unwrap_or(0);
+            if checked_in & bit != 0 && self.inside.contains(&ticket_id) {
+                let record = self
+                    .check_ins
+                    .get(&ticket_id)
+                    .ok_or(Error::CannotFetchValue)?;
+                return Err(Error::AlreadyCheckedIn(record.timestamp, record.scanner));
+            }
+            self.checked_in_days.insert(&ticket_id, &(checked_in | bit));
+            self.check_ins.insert(
+                &ticket_id,
+                &CheckInRecord {
+                    timestamp: self.env().block_timestamp(),
+                    scanner: self.env().caller(),
+                },
+            );
+            if !self.inside.contains(&ticket_id) {
+                self.inside.insert(&ticket_id, &true);
+                self.change_attendance(ticket_id, true);
+            }
+            self.log_change(ChangeKind::CheckIn, ticket_id, self.env().caller());
+            self.env().emit_event(CheckedIn {
+                ticket_id,
+                day,
+                scanner: self.env().caller(),
+            });
+            // After-hooks are notifications and cannot undo the check-in
+            let _ = self.run_hook(
+                HookPoint::AfterCheckIn,
+                ExecutionInput::new(Selector::new(ink_lang::selector_bytes!(
+                    "LifecycleHook::after_check_in"
+                )))
+                .push_arg(ticket_id)
+                .push_arg(day),
+            );
+            Ok(())
+        }
+
+        /// Returns the latest check-in of the ticket ID
+        #[ink(message)]
+        pub fn check_in_of(&self, ticket_id: EventId) -> Option<CheckInRecord> {
+            self.check_ins.get(&ticket_id)
+        }
+
+        /// Fails if the ticket ID has been checked in, a used ticket can't change hands
+        fn ensure_unused(&self, ticket_id: EventId) -> Result<(), Error> {
+            if self.check_ins.contains(&ticket_id) {
+                return Err(Error::TicketUsed);
+            }
+            Ok(())
+        }
+
+        /// Returns the day of the event counted from its start, if it is one of the first 32
+        fn current_day(&self) -> Option<u8> {
+            let event_time = self.event_time?;
+            let elapsed = self.env().block_timestamp().checked_sub(event_time)?;
+            let day = elapsed / DAY;
+            if day >= 32 {
+                return None;
+            }
+            Some(day as u8)
+        }
+
+        /// Assigns the ticket ID to a section of the venue
+        #[ink(message)]
+        pub fn set_ticket_section(
+            &mut self,
+            ticket_id: EventId,
+            section: SectionId,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if self.inside.contains(&ticket_id) {
+                return Err(Error::NotAllowed);
+            }
+            self.ticket_section.insert(&ticket_id, &section);
+            Ok(())
+        }
+
+        /// Returns the section of the venue the ticket ID belongs to
+        #[ink(message)]
+        pub fn section_of(&self, ticket_id: EventId) -> SectionId {
+            self.ticket_section.get(&ticket_id).unwrap_or(0)
+        }
+
+        /// Records the ticket ID leaving the venue, called by a gate scanner
+        #[ink(message)]
+        pub fn check_out(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            self.ensure_role(Role::GateScanner)?;
+            if !self.inside.contains(&ticket_id) {
+                return Err(Error::NotCheckedIn);
+            }
+            self.inside.remove(&ticket_id);
+            self.change_attendance(ticket_id, false);
+            self.env().emit_event(CheckedOut {
+                ticket_id,
+                scanner: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Returns the amount of tickets currently inside the venue
+        #[ink(message)]
+        pub fn current_attendance(&self) -> u32 {
+            self.attendance
+        }
+
+        /// Returns the amount of tickets currently inside the section of the venue
+        #[ink(message)]
+        pub fn section_attendance(&self, section: SectionId) -> u32 {
+            self.section_attendance.get(&section).unwrap_or(0)
+        }
+
+        /// Counts the ticket ID entering or leaving the venue
+        fn change_attendance(&mut self, ticket_id: EventId, entering: bool) {
+            let section = self.section_of(ticket_id);
+            let in_section = self.section_attendance(section);
+            if entering {
+                self.attendance += 1;
+                self.section_attendance.insert(&section, &(in_section + 1));
+            } else {
+                self.attendance -= 1;
+                if in_section == 1 {
+                    self.section_attendance.remove(&section);
+                } else {
+                    self.section_attendance.insert(&section, &(in_section - 1));
+                }
+            }
+        }
+
+        /// Sets the policy for refunding tickets never checked in, `None` stops offering it
+        #[ink(message)]
+        pub fn set_unused_refund_policy(
+            &mut self,
+            policy: Option<UnusedRefundPolicy>,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if let Some(policy) = policy {
+                if !self.non_fungible {
+                    return Err(Error::NonFungibleOnly);
+                }
+                if policy.refund_bps > MAX_BPS || policy.reserve_bps > MAX_BPS {
+                    return Err(Error::InvalidBasisPoints);
+                }
+            }
+            self.unused_refund_policy = policy;
+            Ok(())
+        }
+
+        /// Returns the policy for refunding tickets never checked in, if offered
+        #[ink(message)]
+        pub fn get_unused_refund_policy(&self) -> Option<UnusedRefundPolicy> {
+            self.unused_refund_policy
+        }
+
+        /// Returns the revenue left to refund tickets never checked in
+        #[ink(message)]
+        pub fn get_unused_refund_reserve(&self) -> Balance {
+            self.unused_refund_reserve
+        }
+
+        /// Returns the ticket ID that was never checked in for a partial refund.
+        ///
+        /// The ticket is burned and the refund credited to the pull-payment ledger,
+        /// to be withdrawn with `claim_refund`.
+        #[ink(message)]
+        pub fn return_unused_ticket(&mut self, ticket_id: EventId) -> Result<Balance, Error> {
+            let policy = self.unused_refund_policy.ok_or(Error::NotAllowed)?;
+            self.ensure_event_started()?;
+            let event_time = self.event_time.unwrap_or_default();
+            if self.env().block_timestamp() >= event_time + policy.window {
+                return Err(Error::ClaimsClosed);
+            }
+            self.ensure_ticket_owner(ticket_id)?;
+            self.ensure_unused(ticket_id)?;
+            let face_value = self.face_values.get(&ticket_id).unwrap_or(0);
+            let amount = face_value * Balance::from(policy.refund_bps) / Balance::from(MAX_BPS);
+            if amount == 0 {
+                return Err(Error::NothingToClaim);
+            }
+            if amount > self.unused_refund_reserve {
+                return Err(Error::InsufficientBalance);
+            }
+            let caller = self.env().caller();
+            // The rest of the payment for the ticket is kept by the owner
+            let paid = self.ticket_payments.get(&ticket_id).unwrap_or(0);
+            self.burn_ticket(caller, ticket_id)?;
+            self.unused_refund_reserve -= amount;
+            self.reduce_payment(caller, paid);
+            self.credit_refund(caller, amount)?;
+            Ok(amount)
+        }
+
+        /// Sets the share of the resale premium above face value credited to the original buyer
+        #[cfg(feature = "resale")]
+        #[ink(message)]
+        pub fn set_resale_share(&mut self, share_bps: u16) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if share_bps > MAX_BPS {
+                return Err(Error::InvalidBasisPoints);
+            }
+            self.resale_share_bps = share_bps;
+            Ok(())
+        }
+
+        /// Returns the share of the resale premium credited to the original buyer
+        #[cfg(feature = "resale")]
+        #[ink(message)]
+        pub fn get_resale_share(&self) -> u16 {
+            self.resale_share_bps
+        }
+
+        /// Sets the minimum resale price as a share of face value, 0 for no minimum
+        #[cfg(feature = "resale")]
+        #[ink(message)]
+        pub fn set_resale_floor(&mut self, floor_bps: u16) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if floor_bps > MAX_BPS {
+                return Err(Error::InvalidBasisPoints);
+            }
+            self.resale_floor_bps = floor_bps;
+            Ok(())
+        }
+
+        /// Returns the minimum resale price as a share of face value
+        #[cfg(feature = "resale")]
+        #[ink(message)]
+        pub fn get_resale_floor(&self) -> u16 {
+            self.resale_floor_bps
+        }
+
+        /// Returns the minimum price the ticket ID can be listed for resale at
+        #[cfg(feature = "resale")]
+        #[ink(message)]
+        pub fn resale_floor_of(&self, ticket_id: EventId) -> Balance {
+            let face_value = self.face_values.get(&ticket_id).unwrap_or(0);
+            face_value * Balance::from(self.resale_floor_bps) / Balance::from(MAX_BPS)
+        }
+
+        /// Lists the caller's ticket ID for resale at `price`
+        #[cfg(feature = "resale")]
+        #[ink(message)]
+        pub fn list_for_resale(&mut self, ticket_id: EventId, price: Balance) -> Result<(), Error> {
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            self.ensure_ticket_owner(ticket_id)?;
+            self.ensure_unused(ticket_id)?;
+            self.ensure_unlocked(ticket_id)?;
+            if price == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            if price < self.resale_floor_of(ticket_id) {
+                return Err(Error::BelowPriceFloor);
+            }
+            self.listings.insert(
+                &ticket_id,
+                &Listing {
+                    seller: self.env().caller(),
+                    price,
+                },
+            );
+            Ok(())
+        }
+
+        /// Withdraws the caller's ticket ID from resale
+        #[cfg(feature = "resale")]
+        #[ink(message)]
+        pub fn cancel_listing(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            self.ensure_ticket_owner(ticket_id)?;
+            if !self.listings.contains(&ticket_id) {
+                return Err(Error::NotListed);
+            }
+            self.listings.remove(&ticket_id);
+            Ok(())
+        }
+
+        /// Returns the resale listing of the ticket ID, if listed by its current owner
+        #[cfg(feature = "resale")]
+        #[ink(message)]
+        pub fn get_listing(&self, ticket_id: EventId) -> Option<Listing> {
+            self.listings
+                .get(&ticket_id)
+                .filter(|listing| self.ticket_owner.get(&ticket_id) == Some(listing.seller))
+        }
+
+        /// Buys the listed ticket ID, the transferred value must match the listed price.
+        ///
+        /// The proceeds are credited to the pull-payment ledger, with the original buyer's
+        /// share of any premium above face value split off from the seller's.
+        #[cfg(feature = "resale")]
+        #[ink(message, payable)]
+        pub fn buy_resale(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            let listing = self.get_listing(ticket_id).ok_or(Error::NotListed)?;
+            if self.env().transferred_value() != listing.price {
+                return Err(Error::IncorrectPayment);
+            }
+            let buyer = self.env().caller();
+            self.ensure_unallocated(listing.seller, 1)?;
+            self.transfer(listing.seller, buyer, ticket_id, 1)?;
+            self.listings.remove(&ticket_id);
+
+            let face_value = self.face_values.get(&ticket_id).unwrap_or(0);
+            let premium = listing.price.saturating_sub(face_value);
+            let share = premium * Balance::from(self.resale_share_bps) / Balance::from(MAX_BPS);
+            let original_buyer = self.original_buyers.get(&ticket_id);
+            let share = match original_buyer {
+                Some(original_buyer) if share > 0 => {
+                    self.credit_refund(original_buyer, share)?;
+                    share
+                }
+                _ => 0,
+            };
+            self.credit_refund(listing.seller, listing.price - share)?;
+            self.env().emit_event(Resold {
+                ticket_id,
+                seller: listing.seller,
+                buyer,
+                price: listing.price,
+                original_buyer_share: share,
+            });
+            Ok(())
+        }
+
+        /// Starts an English auction of the unsold ticket ID running for `duration` blocks
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn start_auction(
+            &mut self,
+            ticket_id: EventId,
+            reserve_price: Balance,
+            duration: BlockNumber,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            if self.is_taken(ticket_id) {
+                return Err(Error::TokenExists);
+            }
+            let inventory = self.balance.get(&self.owner).unwrap_or(0);
+            if inventory <= self.auctioned_tickets {
+                return Err(Error::SoldOut);
+            }
+            if duration == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            self.auctions.insert(
+                &ticket_id,
+                &Auction {
+                    reserve_price,
+                    end: self.env().block_number() + duration,
+                    highest_bidder: None,
+                    highest_bid: 0,
+                },
+            );
+            self.auctioned_tickets += 1;
+            Ok(())
+        }
+
+        /// Returns the auction of the ticket ID, if running or awaiting settlement
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn get_auction(&self, ticket_id: EventId) -> Option<Auction> {
+            self.auctions.get(&ticket_id)
+        }
+
+        /// Sets or disables extending auctions that receive bids close to their end
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn set_anti_sniping(&mut self, anti_sniping: Option<AntiSniping>) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.anti_sniping = anti_sniping;
+            Ok(())
+        }
+
+        /// Returns the extension of auctions receiving late bids, if enabled
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn get_anti_sniping(&self) -> Option<AntiSniping> {
+            self.anti_sniping
+        }
+
+        /// Bids the transferred value on the ticket ID, the outbid bidder's bid is
+        /// credited to the pull-payment ledger
+        #[cfg(feature = "auctions")]
+        #[ink(message, payable)]
+        pub fn bid(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            let mut auction = self.auctions.get(&ticket_id).ok_or(Error::NoAuction)?;
+            let block = self.env().block_number();
+            if block >= auction.end {
+                return Err(Error::AuctionEnded);
+            }
+            let amount = self.env().transferred_value();
+            if amount < auction.reserve_price || amount <= auction.highest_bid {
+                return Err(Error::BidTooLow);
+            }
+            if let Some(outbid) = auction.highest_bidder {
+                self.credit_refund(outbid, auction.highest_bid)?;
+                self.escrowed_total -= auction.highest_bid;
+            }
+            self.escrowed_total += amount;
+            if let Some(anti_sniping) = self.anti_sniping {
+                if auction.end - block <= anti_sniping.window {
+                    auction.end += anti_sniping.extension;
+                }
+            }
+            let bidder = self.env().caller();
+            auction.highest_bidder = Some(bidder);
+            auction.highest_bid = amount;
+            self.auctions.insert(&ticket_id, &auction);
+            self.env().emit_event(BidPlaced {
+                ticket_id,
+                bidder,
+                amount,
+                end: auction.end,
+            });
+            Ok(())
+        }
+
+        /// Hands the ticket ID to the highest bidder once the auction has ended,
+        /// returning it to the sale if there were no bids
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn settle_auction(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            let auction = self.auctions.get(&ticket_id).ok_or(Error::NoAuction)?;
+            if self.env().block_number() < auction.end {
+                return Err(Error::AuctionNotEnded);
+            }
+            if let Some(winner) = auction.highest_bidder {
+                // Bids include tax
+                let (price, tax) = self.split_tax(auction.highest_bid);
+                self.move_balance(self.owner, winner, 1)?;
+                self.ticket_owner.insert(&ticket_id, &winner);
+                self.index_ticket(ticket_id);
+                self.face_values.insert(&ticket_id, &price);
+                self.original_buyers.insert(&ticket_id, &winner);
+                self.tickets_sold += 1;
+                self.revenue += price;
+                self.record_ticket_payment(winner, ticket_id, auction.highest_bid);
+                self.invoice(self.receipt(winner, ticket_id, 1, price, tax));
+                self.escrowed_total -= auction.highest_bid;
+            }
+            self.auctions.remove(&ticket_id);
+            self.auctioned_tickets -= 1;
+            Ok(())
+        }
+
+        /// Returns true if the ticket ID is held back from the sale for an auction
+        #[cfg(feature = "auctions")]
+        fn is_auctioned(&self, ticket_id: EventId) -> bool {
+            self.auctions.contains(&ticket_id) || self.sealed_lots.contains(&ticket_id)
+        }
+
+        /// Returns true if the ticket ID is held back from the sale for an auction
+        #[cfg(not(feature = "auctions"))]
+        fn is_auctioned(&self, _ticket_id: EventId) -> bool {
+            false
+        }
+
+        /// Returns true if the seat is held back for an upgrade auction
+        #[cfg(feature = "auctions")]
+        fn is_seat_auctioned(&self, seat: &Seat) -> bool {
+            self.upgrade_auctions.contains(seat)
+        }
+
+        /// Returns true if the seat is held back for an upgrade auction
+        #[cfg(not(feature = "auctions"))]
+        fn is_seat_auctioned(&self, _seat: &Seat) -> bool {
+            false
+        }
+
+        /// Returns true if the ticket ID can't be minted or sold: it is held, up for
+        /// auction, or was refunded or revoked and must stay retired
+        fn is_taken(&self, ticket_id: EventId) -> bool {
+            self.exists(ticket_id)
+                || self.is_auctioned(ticket_id)
+                || self.ticket_status.contains(&ticket_id)
+        }
+
+        /// Starts a sealed-bid auction of `units` unsold ticket IDs starting at `ticket_id`.
+        ///
+        /// Bids are committed for `commit_period` blocks and revealed during the following
+        /// `reveal_period` blocks.
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn start_sealed_auction(
+            &mut self,
+            ticket_id: EventId,
+            units: u32,
+            reserve_price: Balance,
+            commit_period: BlockNumber,
+            reveal_period: BlockNumber,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            if units > MAX_SEALED_UNITS || commit_period == 0 || reveal_period == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            let ticket_ids = Self::ticket_ids(ticket_id, Balance::from(units))?;
+            if ticket_ids.clone().any(|ticket_id| self.is_taken(ticket_id)) {
+                return Err(Error::TokenExists);
+            }
+            let inventory = self.balance.get(&self.owner).unwrap_or(0);
+            if inventory.saturating_sub(self.auctioned_tickets) < Balance::from(units) {
+                return Err(Error::SoldOut);
+            }
+            for lot_ticket_id in ticket_ids {
+                self.sealed_lots.insert(&lot_ticket_id, &ticket_id);
+            }
+            let commit_end = self.env().block_number() + commit_period;
+            self.sealed_auctions.insert(
+                &ticket_id,
+                &SealedAuction {
+                    units,
+                    reserve_price,
+                    commit_end,
+                    reveal_end: commit_end + reveal_period,
+                    escrowed: 0,
+                },
+            );
+            self.auctioned_tickets += Balance::from(units);
+            Ok(())
+        }
+
+        /// Returns the sealed-bid auction starting at the ticket ID, if running or awaiting settlement
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn get_sealed_auction(&self, ticket_id: EventId) -> Option<SealedAuction> {
+            self.sealed_auctions.get(&ticket_id)
+        }
+
+        /// Returns the commitment to submit for a sealed bid of `bid` hidden by `salt`
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn sealed_bid_commitment(&self, bid: Balance, salt: [u8; 32]) -> Hash {
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink_env::hash_encoded::<Blake2x256, _>(&(bid, salt), &mut output);
+            Hash::from(output)
+        }
+
+        /// Commits a hidden bid on the sealed-bid auction, the transferred value is the
+        /// deposit and must cover the bid
+        #[cfg(feature = "auctions")]
+        #[ink(message, payable)]
+        pub fn commit_bid(&mut self, ticket_id: EventId, commitment: Hash) -> Result<(), Error> {
+            let mut auction = self
+                .sealed_auctions
+                .get(&ticket_id)
+                .ok_or(Error::NoAuction)?;
+            if self.env().block_number() >= auction.commit_end {
+                return Err(Error::AuctionEnded);
+            }
+            let key = (ticket_id, self.env().caller());
+            if self.sealed_bids.contains(&key) {
+                return Err(Error::NotAllowed);
+            }
+            let deposit = self.env().transferred_value();
+            if deposit == 0 {
+                return Err(Error::BidTooLow);
+            }
+            self.sealed_bids.insert(
+                &key,
+                &SealedBid {
+                    commitment,
+                    deposit,
+                },
+            );
+            auction.escrowed += deposit;
+            self.sealed_auctions.insert(&ticket_id, &auction);
+            self.escrowed_total += deposit;
+            Ok(())
+        }
+
+        /// Reveals the caller's sealed bid.
+        ///
+        /// The deposit above the bid is credited to the pull-payment ledger right away, and so
+        /// is the whole bid once it falls out of the winning bids. Deposits of bids never
+        /// revealed are forfeited.
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn reveal_bid(
+            &mut self,
+            ticket_id: EventId,
+            bid: Balance,
+            salt: [u8; 32],
+        ) -> Result<(), Error> {
+            let auction = self
+                .sealed_auctions
+                .get(&ticket_id)
+                .ok_or(Error::NoAuction)?;
+            let block = self.env().block_number();
+            if block < auction.commit_end || block >= auction.reveal_end {
+                return Err(Error::BidNotRevealable);
+            }
+            let bidder = self.env().caller();
+            let key = (ticket_id, bidder);
+            let sealed_bid = self.sealed_bids.get(&key).ok_or(Error::BidNotRevealable)?;
+            if self.sealed_bid_commitment(bid, salt) != sealed_bid.commitment {
+                return Err(Error::InvalidReveal);
+            }
+            self.sealed_bids.remove(&key);
+            if bid > sealed_bid.deposit || bid < auction.reserve_price {
+                self.credit_refund(bidder, sealed_bid.deposit)?;
+                self.release_sealed_escrow(ticket_id, auction, sealed_bid.deposit);
+                return Ok(());
+            }
+            let mut released = sealed_bid.deposit - bid;
+            if released > 0 {
+                self.credit_refund(bidder, released)?;
+            }
+
+            // Equal bids keep their reveal order
+            let mut winners = self.sealed_winners.get(&ticket_id).unwrap_or_default();
+            let position = winners
+                .iter()
+                .position(|(_, winning_bid)| *winning_bid < bid)
+                .unwrap_or(winners.len());
+            winners.insert(position, (bidder, bid));
+            if winners.len() > auction.units as usize {
+                if let Some((loser, losing_bid)) = winners.pop() {
+                    self.credit_refund(loser, losing_bid)?;
+                    released += losing_bid;
+                }
+            }
+            self.sealed_winners.insert(&ticket_id, &winners);
+            self.release_sealed_escrow(ticket_id, auction, released);
+            Ok(())
+        }
+
+        /// Releases `amount` of the deposits and bids held for the sealed-bid auction
+        #[cfg(feature = "auctions")]
+        fn release_sealed_escrow(
+            &mut self,
+            ticket_id: EventId,
+            mut auction: SealedAuction,
+            amount: Balance,
+        ) {
+            auction.escrowed -= amount;
+            self.sealed_auctions.insert(&ticket_id, &auction);
+            self.escrowed_total -= amount;
+        }
+
+        /// Hands the lot to the winning bids once the reveal phase is over.
+        ///
+        /// Every winner pays the lowest winning bid, or the reserve price if the lot was not
+        /// fully subscribed, and is credited the rest of their bid. Deposits of bids never
+        /// revealed go to the proceeds. Unsold ticket IDs return to the sale.
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn settle_sealed_auction(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            let auction = self
+                .sealed_auctions
+                .get(&ticket_id)
+                .ok_or(Error::NoAuction)?;
+            if self.env().block_number() < auction.reveal_end {
+                return Err(Error::AuctionNotEnded);
+            }
+            let winners = self.sealed_winners.get(&ticket_id).unwrap_or_default();
+            let clearing_price = match winners.last() {
+                Some((_, lowest_bid)) if winners.len() == auction.units as usize => *lowest_bid,
+                _ => auction.reserve_price,
+            };
+            let inventory = self.balance.get(&self.owner).unwrap_or(0);
+            if inventory < winners.len() as Balance {
+                return Err(Error::SoldOut);
+            }
+
+            let ticket_ids = Self::ticket_ids(ticket_id, Balance::from(auction.units))?;
+            // Bids include tax
+            let (price, tax) = self.split_tax(clearing_price);
+            for (lot_ticket_id, (winner, bid)) in ticket_ids.clone().zip(winners) {
+                self.move_balance(self.owner, winner, 1)?;
+                self.ticket_owner.insert(&lot_ticket_id, &winner);
+                self.index_ticket(lot_ticket_id);
+                self.face_values.insert(&lot_ticket_id, &price);
+                self.original_buyers.insert(&lot_ticket_id, &winner);
+                self.tickets_sold += 1;
+                self.revenue += price;
+                self.record_ticket_payment(winner, lot_ticket_id, clearing_price);
+                self.invoice(self.receipt(winner, lot_ticket_id, 1, price, tax));
+                if bid > clearing_price {
+                    self.credit_refund(winner, bid - clearing_price)?;
+                }
+            }
+            for lot_ticket_id in ticket_ids {
+                self.sealed_lots.remove(&lot_ticket_id);
+            }
+            self.sealed_auctions.remove(&ticket_id);
+            self.sealed_winners.remove(&ticket_id);
+            self.escrowed_total -= auction.escrowed;
+            self.auctioned_tickets -= Balance::from(auction.units);
+            Ok(())
+        }
+
+        /// Adds the account to the accounts `process_refunds` walks, unless it is owed something
+        /// already and so still listed
+        fn track_payer(&mut self, account: AccountId) {
+            if self.payment_of(account) == 0
+                && self.extra_payments.get(&account).unwrap_or(0) == 0
+                && self.credit_of(account) == 0
+            {
+                self.payers.insert(&self.payer_count, &account);
+                self.payer_count += 1;
+            }
+        }
+
+        /// Records an amount collected for tickets the account holds, to be refunded if the
+        /// event is cancelled
+        fn record_payment(&mut self, account: AccountId, amount: Balance) {
+            if amount == 0 {
+                return;
+            }
+            self.track_payer(account);
+            let paid = self.payment_of(account);
+            self.payments.insert(&account, &(paid + amount));
+        }
+
+        /// Records an amount collected for the ticket ID from its holder
+        fn record_ticket_payment(
+            &mut self,
+            holder: AccountId,
+            ticket_id: EventId,
+            amount: Balance,
+        ) {
+            let paid = self.ticket_payments.get(&ticket_id).unwrap_or(0);
+            self.ticket_payments.insert(&ticket_id, &(paid + amount));
+            self.record_payment(holder, amount);
+        }
+
+        /// Records an amount paid for vouchers or parking passes, to be refunded if the event
+        /// is cancelled
+        fn record_extra_payment(&mut self, account: AccountId, amount: Balance) {
+            if amount == 0 {
+                return;
+            }
+            self.track_payer(account);
+            let paid = self.extra_payments.get(&account).unwrap_or(0);
+            self.extra_payments.insert(&account, &(paid + amount));
+        }
+
+        /// Lowers the amount the account paid, removing the entry once nothing is left
+        fn reduce_payment(&mut self, account: AccountId, amount: Balance) {
+            let paid = self.payment_of(account).saturating_sub(amount);
+            if paid == 0 {
+                self.payments.remove(&account);
+            } else {
+                self.payments.insert(&account, &paid);
+            }
+        }
+
+        /// Returns the amount collected for the tickets the account holds and not refunded
+        #[ink(message)]
+        pub fn payment_of(&self, account: AccountId) -> Balance {
+            self.payments.get(&account).unwrap_or(0)
+        }
+
+        /// Cancels the event, closing the sale for good so paid amounts can be refunded
+        /// with `process_refunds`
+        #[ink(message)]
+        pub fn cancel_event(&mut self) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if self.cancelled {
+                return Err(Error::EventCancelled);
+            }
+            self.cancel();
+            Ok(())
+        }
+
+        /// Cancels the event for good. The carbon offsets won't be bought, they are refunded
+        /// along with the tickets.
+        fn cancel(&mut self) {
+            self.cancelled = true;
+            self.sale_open = false;
+            self.carbon_offset_bucket = 0;
+        }
+
+        /// Returns true if the event is cancelled
+        #[ink(message)]
+        pub fn is_cancelled(&self) -> bool {
+            self.cancelled
+        }
+
+        /// Credits up to `limit` accounts of a cancelled event everything collected for the
+        /// tickets they hold, along with what they paid for vouchers, parking and unspent credit,
+        /// to be withdrawn with `claim_refund`.
+        ///
+        /// Returns the number of accounts processed; zero means every account has been refunded.
+        #[ink(message)]
+        pub fn process_refunds(&mut self, limit: u32) -> Result<u32, Error> {
+            self.ensure_owner()?;
+            if !self.cancelled {
+                return Err(Error::NotAllowed);
+            }
+            let end = self
+                .payer_count
+                .min(self.refund_cursor.saturating_add(limit));
+            for index in self.refund_cursor..end {
+                if let Some(account) = self.payers.get(&index) {
+                    match self.refund_payment(account) {
+                        Ok(_) | Err(Error::NothingToClaim) => {}
+                        Err(error) => return Err(error),
+                    }
+                    self.payers.remove(&index);
+                }
+            }
+            let processed = end - self.refund_cursor;
+            self.refund_cursor = end;
+            Ok(processed)
+        }
+
+        /// Enables or disables selling tickets only through the purchase queue
+        #[ink(message)]
+        pub fn set_queue_mode(&mut self, enabled: bool) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.queue_mode = enabled;
+            Ok(())
+        }
+
+        /// Returns true if tickets can only be bought through the purchase queue
+        #[ink(message)]
+        pub fn is_queue_mode(&self) -> bool {
+            self.queue_mode
+        }
+
+        /// Queues a purchase of tickets of the ticket ID, holding the transferred value
+        /// as payment. Returns the position in the queue.
+        #[ink(message, payable)]
+        pub fn enqueue_purchase(
+            &mut self,
+            event_id: EventId,
+            quantity: Balance,
+        ) -> Result<u64, Error> {
+            if !self.queue_mode {
+                return Err(Error::NotAllowed);
+            }
+            if !self.sale_open {
+                return Err(Error::SaleNotOpen);
+            }
+            if quantity == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            // An intent larger than the throttle could never be fulfilled and would block the queue
+            if self.max_tickets_per_block != 0 && quantity > self.max_tickets_per_block {
+                return Err(Error::ThrottleExceeded);
+            }
+            if self.env().transferred_value() != self.get_quote(event_id, quantity)? {
+                return Err(Error::IncorrectPayment);
+            }
+            let position = self.queue_tail;
+            self.escrowed_total += self.env().transferred_value();
+            self.purchase_queue.insert(
+                &position,
+                &PurchaseIntent {
+                    buyer: self.env().caller(),
+                    ticket_id: event_id,
+                    quantity,
+                    payment: self.env().transferred_value(),
+                },
+            );
+            self.queue_tail += 1;
+            Ok(position)
+        }
+
+        /// Returns the purchase intent at the position, if still waiting
+        #[ink(message)]
+        pub fn queued_purchase(&self, position: u64) -> Option<PurchaseIntent> {
+            self.purchase_queue.get(&position)
+        }
+
+        /// Returns the amount of purchase intents waiting in the queue
+        #[ink(message)]
+        pub fn queue_length(&self) -> u64 {
+            self.queue_tail - self.queue_head
+        }
+
+        /// Fulfills up to `limit` purchase intents strictly in arrival order.
+        ///
+        /// Intents that can't be fulfilled, e.g. because the tickets are sold out or the
+        /// price changed, have their payment credited to the pull-payment ledger. Stops
+        /// early if the circuit breaker pauses the contract or the block's throttle is
+        /// reached. Returns the number of intents processed.
+        #[ink(message)]
+        pub fn crank_queue(&mut self, limit: u32) -> Result<u32, Error> {
+            let mut processed = 0;
+            while processed < limit && self.queue_head < self.queue_tail {
+                let position = self.queue_head;
+                if let Some(intent) = self.purchase_queue.get(&position) {
+                    match self.purchase_for(
+                        intent.buyer,
+                        intent.ticket_id,
+                        intent.quantity,
+                        PurchaseOptions::default(),
+                        intent.payment,
+                    ) {
+                        Ok(()) => {}
+                        Err(Error::Paused) => break,
+                        // Wait for the next block unless the throttle was lowered below the intent
+                        Err(Error::ThrottleExceeded)
+                            if intent.quantity <= self.max_tickets_per_block =>
+                        {
+                            break
+                        }
+                        Err(_) => self.credit_refund(intent.buyer, intent.payment)?,
+                    }
+                    self.escrowed_total -= intent.payment;
+                    self.purchase_queue.remove(&position);
+                }
+                self.queue_head += 1;
+                processed += 1;
+            }
+            Ok(processed)
+        }
+
+        /// Sets the maximum amount of tickets sold per block, 0 for no limit
+        #[ink(message)]
+        pub fn set_purchase_throttle(
+            &mut self,
+            max_tickets_per_block: Balance,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            self.max_tickets_per_block = max_tickets_per_block;
+            Ok(())
+        }
+
+        /// Returns the maximum amount of tickets sold per block, 0 for no limit
+        #[ink(message)]
+        pub fn get_purchase_throttle(&self) -> Balance {
+            self.max_tickets_per_block
+        }
+
+        /// Fails if selling `quantity` more tickets would exceed this block's throttle
+        fn ensure_throttle(&self, quantity: Balance) -> Result<(), Error> {
+            if self.max_tickets_per_block == 0 {
+                return Ok(());
+            }
+            let sold = if self.activity_block == self.env().block_number() {
+                self.block_purchases
+            } else {
+                0
+            };
+            if sold.saturating_add(quantity) > self.max_tickets_per_block {
+                return Err(Error::ThrottleExceeded);
+            }
+            Ok(())
+        }
+
+        /// Sets the owner inactivity before the event after which holders can refund
+        /// themselves, `None` disables it
+        #[ink(message)]
+        pub fn set_dead_man_period(&mut self, period: Option<Timestamp>) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if period == Some(0) {
+                return Err(Error::InvalidAmount);
+            }
+            self.dead_man_period = period;
+            Ok(())
+        }
+
+        /// Returns the owner inactivity after which holders can refund themselves, if enabled
+        #[ink(message)]
+        pub fn get_dead_man_period(&self) -> Option<Timestamp> {
+            self.dead_man_period
+        }
+
+        /// Returns the last time the owner performed an admin action
+        #[ink(message)]
+        pub fn get_last_admin_action(&self) -> Timestamp {
+            self.last_admin_action
+        }
+
+        /// Returns true if the owner has been inactive for the dead-man period before the event
+        #[ink(message)]
+        pub fn is_abandoned(&self) -> bool {
+            let period = match self.dead_man_period {
+                Some(period) => period,
+                None => return false,
+            };
+            let now = self.env().block_timestamp();
+            let until = match self.event_time {
+                Some(event_time) => now.min(event_time),
+                None => now,
+            };
+            self.last_admin_action.saturating_add(period) <= until
+        }
+
+        /// Credits the caller the amount they paid for tickets once the event is abandoned,
+        /// to be withdrawn with `claim_refund`. The event is cancelled by the first refund.
+        #[ink(message)]
+        pub fn refund_abandoned(&mut self) -> Result<Balance, Error> {
+            if !self.is_abandoned() {
+                return Err(Error::NotAllowed);
+            }
+            self.cancel();
+            self.refund_payment(self.env().caller())
+        }
+
+        /// Credits the caller everything collected for the tickets they hold of a cancelled
+        /// event, along with what they paid for vouchers, parking and unspent credit,
+        /// to be withdrawn with `claim_refund`
+        #[ink(message)]
+        pub fn claim_cancellation_refund(&mut self) -> Result<Balance, Error> {
+            if !self.cancelled {
+                return Err(Error::NotAllowed);
+            }
+            self.refund_payment(self.env().caller())
+        }
+
+        /// Moves the amount collected for the tickets the account holds, its vouchers and
+        /// parking passes and its unspent credit to the pull-payment ledger
+        fn refund_payment(&mut self, account: AccountId) -> Result<Balance, Error> {
+            let credit = self.credit_of(account);
+            let amount =
+                self.payment_of(account) + self.extra_payments.get(&account).unwrap_or(0) + credit;
+            if amount == 0 {
+                return Err(Error::NothingToClaim);
+            }
+            if credit > 0 {
+                self.take_credit(account, credit)?;
+            }
+            self.payments.remove(&account);
+            self.extra_payments.remove(&account);
+            self.credit_refund(account, amount)?;
+            Ok(amount)
+        }
+
+        /// Sets the account that can veto emergency withdrawals.
+        ///
+        /// The owner can only set the first guardian, after that only the guardian can hand over.
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn set_guardian(&mut self, guardian: AccountId) -> Result<(), Error> {
+            match self.guardian {
+                Some(current) if current != self.env().caller() => return Err(Error::NotGuardian),
+                Some(_) => {}
+                None => self.ensure_owner()?,
+            }
+            self.guardian = Some(guardian);
+            Ok(())
+        }
+
+        /// Returns the account that can veto emergency withdrawals, if set
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn get_guardian(&self) -> Option<AccountId> {
+            self.guardian
+        }
+
+        /// Announces an emergency withdrawal of `amount` to `to`, executable with
+        /// `emergency_withdraw` once `EMERGENCY_WITHDRAWAL_DELAY` has passed.
+        ///
+        /// A guardian able to veto it must be set, and the funds reserved for holders and the
+        /// carbon offset partner can't be withdrawn.
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn announce_emergency_withdraw(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if self.guardian.is_none() {
+                return Err(Error::NoGuardian);
+            }
+            if self.pending_withdrawal.is_some() {
+                return Err(Error::NotAllowed);
+            }
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            if amount > self.emergency_withdrawable() {
+                return Err(Error::InsufficientBalance);
+            }
+            let executable_at = self.env().block_timestamp() + EMERGENCY_WITHDRAWAL_DELAY;
+            self.pending_withdrawal = Some(EmergencyWithdrawal {
+                to,
+                amount,
+                executable_at,
+            });
+            self.env().emit_event(EmergencyWithdrawalAnnounced {
+                to,
+                amount,
+                executable_at,
+            });
+            Ok(())
+        }
+
+        /// Returns the part of the contract balance not reserved by `reserved_funds`
+        #[cfg(feature = "governance")]
+        fn emergency_withdrawable(&self) -> Balance {
+            self.env().balance().saturating_sub(self.reserved_funds())
+        }
+
+        /// Returns the announced emergency withdrawal, if any
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn get_pending_withdrawal(&self) -> Option<EmergencyWithdrawal> {
+            self.pending_withdrawal
+        }
+
+        /// Cancels the announced emergency withdrawal, called by the guardian to veto it
+        /// or by the owner to withdraw it
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn cancel_emergency_withdraw(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.guardian != Some(caller) {
+                self.ensure_owner()?;
+            }
+            if self.pending_withdrawal.is_none() {
+                return Err(Error::NoPendingWithdrawal);
+            }
+            self.pending_withdrawal = None;
+            self.env()
+                .emit_event(EmergencyWithdrawalCancelled { by: caller });
+            Ok(())
+        }
+
+        /// Executes the announced emergency withdrawal once its timelock has passed
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn emergency_withdraw(&mut self) -> Result<Balance, Error> {
+            self.ensure_owner()?;
+            let withdrawal = self.pending_withdrawal.ok_or(Error::NoPendingWithdrawal)?;
+            if self.env().block_timestamp() < withdrawal.executable_at {
+                return Err(Error::TimelockActive);
+            }
+            if withdrawal.amount > self.emergency_withdrawable() {
+                return Err(Error::InsufficientBalance);
+            }
+            self.env()
+                .transfer(withdrawal.to, withdrawal.amount)
+                .map_err(|_| Error::TransferFailed)?;
+            self.pending_withdrawal = None;
+            Ok(withdrawal.amount)
+        }
+
+        /// Sets or removes the external condition that cancels the event
+        #[ink(message)]
+        pub fn set_refund_condition(
+            &mut self,
+            condition: Option<RefundCondition>,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if let Some(condition) = condition {
+                if condition.from >= condition.until {
+                    return Err(Error::InvalidAmount);
+                }
+            }
+            self.refund_condition = condition;
+            Ok(())
+        }
+
+        /// Returns the external condition that cancels the event, if set
+        #[ink(message)]
+        pub fn get_refund_condition(&self) -> Option<RefundCondition> {
+            self.refund_condition
+        }
+
+        /// Asks the oracle whether the refund condition occurred and cancels the event if so,
+        /// letting holders claim their refunds. Callable by anyone.
+        #[ink(message)]
+        pub fn trigger_refund_condition(&mut self) -> Result<(), Error> {
+            let condition = self.refund_condition.ok_or(Error::NotAllowed)?;
+            if self.cancelled {
+                return Err(Error::EventCancelled);
+            }
+            let met = build_call::<ink_env::DefaultEnvironment>()
+                .call_type(Call::new().callee(condition.oracle).gas_limit(MAX_HOOK_GAS))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink_lang::selector_bytes!(
+                        "RefundOracle::condition_met"
+                    )))
+                    .push_arg(condition.from)
+                    .push_arg(condition.until),
+                )
+                .returns::<bool>()
+                .fire()
+                .map_err(|_| Error::OracleFailed)?;
+            if !met {
+                return Err(Error::ConditionNotMet);
+            }
+            self.cancel();
+            self.env().emit_event(RefundConditionTriggered {
+                oracle: condition.oracle,
+            });
+            Ok(())
+        }
+
+        /// Sets the rows and seats of a seated section. Once the sale is open, only sections
+        /// without sold or auctioned seats can be changed.
+        #[ink(message)]
+        pub fn set_section_layout(
+            &mut self,
+            section: SectionId,
+            layout: SectionLayout,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if let Some(current) = self.section_layouts.get(&section) {
+                if Self::seats_of(section, current)
+                    .any(|seat| self.seat_tickets.contains(&seat) || self.is_seat_auctioned(&seat))
+                {
+                    return Err(Error::SeatsSold);
+                }
+            }
+            let seats = layout
+                .rows
+                .checked_mul(layout.seats_per_row)
+                .ok_or(Error::InvalidAmount)?;
+            if seats == 0 || seats > MAX_SECTION_SEATS {
+                return Err(Error::InvalidAmount);
+            }
+            self.section_layouts.insert(&section, &layout);
+            Ok(())
+        }
+
+        /// Returns every seat of the section, row by row
+        fn seats_of(section: SectionId, layout: SectionLayout) -> impl Iterator<Item = Seat> {
+            (1..=layout.rows).flat_map(move |row| {
+                (1..=layout.seats_per_row).map(move |number| Seat {
+                    section,
+                    row,
+                    number,
+                })
+            })
+        }
+
+        /// Returns the rows and seats of a seated section, if any
+        #[ink(message)]
+        pub fn get_section_layout(&self, section: SectionId) -> Option<SectionLayout> {
+            self.section_layouts.get(&section)
+        }
+
+        /// Buys `quantity` seated tickets starting at the ticket ID in the section.
+        ///
+        /// The seats are next to each other in one row where possible. Otherwise they are
+        /// scattered over the section if `allow_scattered` is set, and the call fails with
+        /// `NoAdjacentSeats` if not. Returns true if the seats are adjacent.
+        #[ink(message, payable)]
+        pub fn buy_seated_tickets(
+            &mut self,
+            event_id: EventId,
+            quantity: Balance,
+            section: SectionId,
+            allow_scattered: bool,
+        ) -> Result<bool, Error> {
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            let (seats, adjacent) = self.allocate_seats(section, quantity)?;
+            if !adjacent && !allow_scattered {
+                return Err(Error::NoAdjacentSeats);
+            }
+            self.purchase(event_id, quantity, PurchaseOptions::default())?;
+            for (ticket_id, seat) in Self::ticket_ids(event_id, quantity)?.zip(seats) {
+                self.assign_seat(ticket_id, seat)?;
+            }
+            Ok(adjacent)
+        }
+
+        /// Returns the seat of the ticket ID, if seated
+        #[ink(message)]
+        pub fn seat_of(&self, ticket_id: EventId) -> Option<Seat> {
+            self.ticket_seats.get(&ticket_id)
+        }
+
+        /// Returns the ticket ID holding the seat, if taken
+        #[ink(message)]
+        pub fn ticket_at(&self, section: SectionId, row: u32, number: u32) -> Option<EventId> {
+            self.seat_tickets.get(&Seat {
+                section,
+                row,
+                number,
+            })
+        }
+
+        /// Finds `quantity` free seats in the section, adjacent in one row if possible.
+        /// Returns the seats and whether they are adjacent.
+        fn allocate_seats(
+            &self,
+            section: SectionId,
+            quantity: Balance,
+        ) -> Result<(Vec<Seat>, bool), Error> {
+            let layout = self
+                .section_layouts
+                .get(&section)
+                .ok_or(Error::UnknownSection)?;
+            if quantity == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            let mut free = Vec::new();
+            for row in 1..=layout.rows {
+                let mut run = Vec::new();
+                for number in 1..=layout.seats_per_row {
+                    let seat = Seat {
+                        section,
+                        row,
+                        number,
+                    };
+                    if self.seat_tickets.contains(&seat)
+                        || self.is_seat_auctioned(&seat)
+                        || self.retired_seats.contains(&seat)
+                    {
+                        run.clear();
+                        continue;
+                    }
+                    run.push(seat);
+                    if (free.len() as Balance) < quantity {
+                        free.push(seat);
+                    }
+                    if run.len() as Balance == quantity {
+                        return Ok((run, true));
+                    }
+                }
+            }
+            if (free.len() as Balance) < quantity {
+                return Err(Error::SoldOut);
+            }
+            Ok((free, false))
+        }
+
+        /// Gives the seat to the ticket ID, releasing any seat it held before
+        fn assign_seat(&mut self, ticket_id: EventId, seat: Seat) -> Result<(), Error> {
+            if self.retired_seats.contains(&seat) {
+                return Err(Error::SeatRetired);
+            }
+            if let Some(previous) = self.ticket_seats.get(&ticket_id) {
+                if self.seat_tickets.get(&previous) == Some(ticket_id) {
+                    self.seat_tickets.remove(&previous);
+                }
+            }
+            self.seat_tickets.insert(&seat, &ticket_id);
+            self.ticket_seats.insert(&ticket_id, &seat);
+            self.ticket_section.insert(&ticket_id, &seat.section);
+            Ok(())
+        }
+
+        /// Posts a request to swap the seat of the caller's ticket ID for a seat in rows
+        /// `min_row` to `max_row` of the section, replacing any earlier request
+        #[ink(message)]
+        pub fn request_seat_swap(
+            &mut self,
+            ticket_id: EventId,
+            section: SectionId,
+            min_row: u32,
+            max_row: u32,
+        ) -> Result<(), Error> {
+            self.ensure_ticket_owner(ticket_id)?;
+            self.ensure_unused(ticket_id)?;
+            if !self.ticket_seats.contains(&ticket_id) {
+                return Err(Error::NotAllowed);
+            }
+            if min_row == 0 || min_row > max_row {
+                return Err(Error::InvalidAmount);
+            }
+            self.swap_requests.insert(
+                &ticket_id,
+                &SwapRequest {
+                    holder: self.env().caller(),
+                    section,
+                    min_row,
+                    max_row,
+                },
+            );
+            Ok(())
+        }
+
+        /// Withdraws the swap request of the caller's ticket ID
+        #[ink(message)]
+        pub fn cancel_seat_swap(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            self.ensure_ticket_owner(ticket_id)?;
+            if !self.swap_requests.contains(&ticket_id) {
+                return Err(Error::NoSwapRequest);
+            }
+            self.swap_requests.remove(&ticket_id);
+            Ok(())
+        }
+
+        /// Returns the swap request of the ticket ID, if posted by its current holder
+        #[ink(message)]
+        pub fn get_seat_swap(&self, ticket_id: EventId) -> Option<SwapRequest> {
+            self.swap_requests
+                .get(&ticket_id)
+                .filter(|request| self.ticket_owner.get(&ticket_id) == Some(request.holder))
+        }
+
+        /// Accepts the swap request of `other_ticket_id` with the caller's ticket ID,
+        /// exchanging the seats of both
+        #[ink(message)]
+        pub fn accept_seat_swap(
+            &mut self,
+            ticket_id: EventId,
+            other_ticket_id: EventId,
+        ) -> Result<(), Error> {
+            self.ensure_ticket_owner(ticket_id)?;
+            let request = self
+                .get_seat_swap(other_ticket_id)
+                .ok_or(Error::NoSwapRequest)?;
+            self.ensure_unused(ticket_id)?;
+            self.ensure_unused(other_ticket_id)?;
+            let seat = self.ticket_seats.get(&ticket_id).ok_or(Error::NotAllowed)?;
+            let other_seat = self
+                .ticket_seats
+                .get(&other_ticket_id)
+                .ok_or(Error::NotAllowed)?;
+            if seat.section != request.section
+                || seat.row < request.min_row
+                || seat.row > request.max_row
+            {
+                return Err(Error::SeatNotWanted);
+            }
+            self.assign_seat(ticket_id, other_seat)?;
+            self.assign_seat(other_ticket_id, seat)?;
+            self.swap_requests.remove(&ticket_id);
+            self.swap_requests.remove(&other_ticket_id);
+            self.env().emit_event(SeatsSwapped {
+                ticket_id,
+                other_ticket_id,
+            });
+            Ok(())
+        }
+
+        /// Starts an auction of the free seat running for `duration` blocks, open to
+        /// holders of seated ticket IDs bidding what they pay to move up
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn start_upgrade_auction(
+            &mut self,
+            seat: Seat,
+            reserve_price: Balance,
+            duration: BlockNumber,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            let layout = self
+                .section_layouts
+                .get(&seat.section)
+                .ok_or(Error::UnknownSection)?;
+            if seat.row == 0
+                || seat.row > layout.rows
+                || seat.number == 0
+                || seat.number > layout.seats_per_row
+                || duration == 0
+            {
+                return Err(Error::InvalidAmount);
+            }
+            if self.retired_seats.contains(&seat) {
+                return Err(Error::SeatRetired);
+            }
+            if self.seat_tickets.contains(&seat) || self.upgrade_auctions.contains(&seat) {
+                return Err(Error::TokenExists);
+            }
+            self.upgrade_auctions.insert(
+                &seat,
+                &UpgradeAuction {
+                    reserve_price,
+                    end: self.env().block_number() + duration,
+                    highest_bidder: None,
+                    ticket_id: 0,
+                    highest_bid: 0,
+                },
+            );
+            Ok(())
+        }
+
+        /// Returns the upgrade auction of the seat, if running or awaiting settlement
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn get_upgrade_auction(&self, seat: Seat) -> Option<UpgradeAuction> {
+            self.upgrade_auctions.get(&seat)
+        }
+
+        /// Bids the transferred value to move the caller's seated ticket ID to the seat,
+        /// the outbid bidder's bid is credited to the pull-payment ledger
+        #[cfg(feature = "auctions")]
+        #[ink(message, payable)]
+        pub fn bid_upgrade(&mut self, seat: Seat, ticket_id: EventId) -> Result<(), Error> {
+            let mut auction = self.upgrade_auctions.get(&seat).ok_or(Error::NoAuction)?;
+            if self.env().block_number() >= auction.end {
+                return Err(Error::AuctionEnded);
+            }
+            self.ensure_ticket_owner(ticket_id)?;
+            self.ensure_unused(ticket_id)?;
+            if !self.ticket_seats.contains(&ticket_id) {
+                return Err(Error::NotAllowed);
+            }
+            let amount = self.env().transferred_value();
+            if amount < auction.reserve_price || amount <= auction.highest_bid {
+                return Err(Error::BidTooLow);
+            }
+            if let Some(outbid) = auction.highest_bidder {
+                self.credit_refund(outbid, auction.highest_bid)?;
+                self.escrowed_total -= auction.highest_bid;
+            }
+            self.escrowed_total += amount;
+            let bidder = self.env().caller();
+            auction.highest_bidder = Some(bidder);
+            auction.ticket_id = ticket_id;
+            auction.highest_bid = amount;
+            self.upgrade_auctions.insert(&seat, &auction);
+            self.env().emit_event(BidPlaced {
+                ticket_id,
+                bidder,
+                amount,
+                end: auction.end,
+            });
+            Ok(())
+        }
+
+        /// Moves the winning ticket ID to the seat once the auction has ended, releasing its
+        /// old seat. The bid is refunded instead if the ticket ID changed hands meanwhile or
+        /// the seat was withdrawn by a capacity reduction.
+        #[cfg(feature = "auctions")]
+        #[ink(message)]
+        pub fn settle_upgrade_auction(&mut self, seat: Seat) -> Result<(), Error> {
+            let auction = self.upgrade_auctions.get(&seat).ok_or(Error::NoAuction)?;
+            if self.env().block_number() < auction.end {
+                return Err(Error::AuctionNotEnded);
+            }
+            self.upgrade_auctions.remove(&seat);
+            if let Some(winner) = auction.highest_bidder {
+                self.escrowed_total -= auction.highest_bid;
+                if self.ticket_owner.get(&auction.ticket_id) == Some(winner)
+                    && !self.check_ins.contains(&auction.ticket_id)
+                    && !self.retired_seats.contains(&seat)
+                {
+                    // Bids include tax
+                    let (price, tax) = self.split_tax(auction.highest_bid);
+                    self.assign_seat(auction.ticket_id, seat)?;
+                    self.revenue += price;
+                    self.record_ticket_payment(winner, auction.ticket_id, auction.highest_bid);
+                    let receipt = Receipt {
+                        upgrade: Some(seat),
+                        ..self.receipt(winner, auction.ticket_id, 0, price, tax)
+                    };
+                    self.invoice(receipt);
+                } else {
+                    self.credit_refund(winner, auction.highest_bid)?;
+                }
+            }
+            Ok(())
+        }
+
+        /// Announces refunding the targeted tickets to reduce the capacity of the venue,
+        /// executable with `execute_capacity_reduction` once `CAPACITY_REDUCTION_DELAY` has
+        /// passed. The guardian can veto it meanwhile.
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn announce_capacity_reduction(
+            &mut self,
+            target: ReductionTarget,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            if self.pending_reduction.is_some() {
+                return Err(Error::NotAllowed);
+            }
+            match target {
+                ReductionTarget::TicketIds { first, count } => {
+                    if count > MAX_REDUCTION_TICKETS {
+                        return Err(Error::InvalidAmount);
+                    }
+                    Self::ticket_ids(first, Balance::from(count))?;
+                }
+                ReductionTarget::Section(section) => {
+                    if !self.section_layouts.contains(&section) {
+                        return Err(Error::UnknownSection);
+                    }
+                }
+            }
+            self.pending_reduction = Some(CapacityReduction {
+                target,
+                executable_at: self.env().block_timestamp() + CAPACITY_REDUCTION_DELAY,
+            });
+            Ok(())
+        }
+
+        /// Returns the announced capacity reduction, if any
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn get_pending_reduction(&self) -> Option<CapacityReduction> {
+            self.pending_reduction
+        }
+
+        /// Cancels the announced capacity reduction, called by the guardian to veto it
+        /// or by the owner to withdraw it
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn cancel_capacity_reduction(&mut self) -> Result<(), Error> {
+            if self.guardian != Some(self.env().caller()) {
+                self.ensure_owner()?;
+            }
+            if self.pending_reduction.is_none() {
+                return Err(Error::NoPendingReduction);
+            }
+            self.pending_reduction = None;
+            Ok(())
+        }
+
+        /// Executes the announced capacity reduction once its timelock has passed.
+        ///
+        /// Every sold ticket ID targeted is marked `Refunded` and its holder credited
+        /// everything collected for it on the pull-payment ledger. Targeted ticket IDs the
+        /// owner holds are burned, unsold ones are marked `Withdrawn` and free seats of a
+        /// targeted section are retired, each taking one ticket out of the owner's inventory.
+        /// Returns the number of ticket IDs refunded.
+        #[cfg(feature = "governance")]
+        #[ink(message)]
+        pub fn execute_capacity_reduction(&mut self) -> Result<u32, Error> {
+            self.ensure_owner()?;
+            let reduction = self.pending_reduction.ok_or(Error::NoPendingReduction)?;
+            if self.env().block_timestamp() < reduction.executable_at {
+                return Err(Error::TimelockActive);
+            }
+            self.pending_reduction = None;
+            let mut refunded = 0;
+            let mut unsold = 0;
+            match reduction.target {
+                ReductionTarget::TicketIds { first, count } => {
+                    for ticket_id in Self::ticket_ids(first, Balance::from(count))? {
+                        if self.exists(ticket_id) {
+                            if self.refund_ticket(ticket_id)?.is_some() {
+                                refunded += 1;
+                            }
+                        } else if !self.is_taken(ticket_id) {
+                            self.ticket_status
+                                .insert(&ticket_id, &TicketStatus::Withdrawn);
+                            unsold += 1;
+                        }
+                    }
+                }
+                ReductionTarget::Section(section) => {
+                    let layout = self
+                        .section_layouts
+                        .get(&section)
+                        .ok_or(Error::UnknownSection)?;
+                    for seat in Self::seats_of(section, layout) {
+                        match self.seat_tickets.get(&seat) {
+                            Some(ticket_id) => {
+                                if self.refund_ticket(ticket_id)?.is_some() {
+                                    refunded += 1;
+                                }
+                            }
+                            None if !self.retired_seats.contains(&seat) => {
+                                self.retired_seats.insert(&seat, &());
+                                unsold += 1;
+                            }
+                            None => {}
+                        }
+                    }
+                }
+            }
+            self.withdraw_inventory(unsold);
+            Ok(refunded)
+        }
+
+        /// Takes up to `quantity` unsold tickets out of the owner's inventory for good,
+        /// leaving the tickets held back for auctions
+        #[cfg(feature = "governance")]
+        fn withdraw_inventory(&mut self, quantity: Balance) {
+            let inventory = self.balance.get(&self.owner).unwrap_or(0);
+            let withdrawn = quantity.min(inventory.saturating_sub(self.auctioned_tickets));
+            if inventory == withdrawn {
+                self.balance.remove(&self.owner);
+            } else {
+                self.balance.insert(&self.owner, &(inventory - withdrawn));
+            }
+            self.total_tickets = self.total_tickets.saturating_sub(withdrawn);
+        }
+
+        /// Returns why the ticket ID is no longer valid, if it isn't
+        #[ink(message)]
+        pub fn ticket_status(&self, ticket_id: EventId) -> Option<TicketStatus> {
+            self.ticket_status.get(&ticket_id)
+        }
+
+        /// Burns the ticket ID, crediting a buyer holding it everything collected for it.
+        /// Returns the buyer, or `None` if the ticket ID was held by the owner or not issued.
+        #[cfg(feature = "governance")]
+        fn refund_ticket(&mut self, ticket_id: EventId) -> Result<Option<AccountId>, Error> {
+            let holder = match self.ticket_owner.get(&ticket_id) {
+                Some(holder) => holder,
+                None => return Ok(None),
+            };
+            if holder == self.owner {
+                self.burn_ticket(holder, ticket_id)?;
+                return Ok(None);
+            }
+            let amount = self.ticket_payments.get(&ticket_id).unwrap_or(0);
+            self.burn_ticket(holder, ticket_id)?;
+            if amount > 0 {
+                self.reduce_payment(holder, amount);
+                self.credit_refund(holder, amount)?;
+            }
+            self.env().emit_event(TicketRefunded {
+                ticket_id,
+                holder,
+                amount,
+            });
+            Ok(Some(holder))
+        }
+
+        /// Burns the refunded ticket ID of the holder along with its listing, seat and
+        /// pending offers, and logs it so gates stop accepting it. The seat is withdrawn
+        /// from the event, not put back on sale.
+        fn burn_ticket(&mut self, holder: AccountId, ticket_id: EventId) -> Result<(), Error> {
+            self.remove_token_from(holder, ticket_id)?;
+            self.face_values.remove(&ticket_id);
+            self.ticket_payments.remove(&ticket_id);
+            #[cfg(feature = "resale")]
+            self.listings.remove(&ticket_id);
+            self.swap_requests.remove(&ticket_id);
+            self.pending_transfers.remove(&ticket_id);
+            if let Some(seat) = self.ticket_seats.get(&ticket_id) {
+                self.seat_tickets.remove(&seat);
+                self.ticket_seats.remove(&ticket_id);
+                self.retired_seats.insert(&seat, &());
+            }
+            self.ticket_status
+                .insert(&ticket_id, &TicketStatus::Refunded);
+            self.total_tickets = self.total_tickets.saturating_sub(1);
+            self.log_change(ChangeKind::Refund, ticket_id, holder);
+            Ok(())
+        }
+
+        /// Sets the price and supply of an add-on experience, keeping its sales
+        #[ink(message)]
+        pub fn set_add_on(
+            &mut self,
+            add_on: AddOnId,
+            price: Balance,
+            supply: u32,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if add_on >= 32 {
+                return Err(Error::UnknownAddOn);
+            }
+            let sold = self.add_ons.get(&add_on).map_or(0, |terms| terms.sold);
+            if supply < sold {
+                return Err(Error::InvalidAmount);
+            }
+            self.add_ons.insert(
+                &add_on,
+                &AddOnTerms {
+                    price,
+                    supply,
+                    sold,
+                },
+            );
+            Ok(())
+        }
+
+        /// Returns the price and supply of an add-on experience, if offered
+        #[ink(message)]
+        pub fn get_add_on(&self, add_on: AddOnId) -> Option<AddOnTerms> {
+            self.add_ons.get(&add_on)
+        }
+
+        /// Returns the add-ons bought for the ticket ID as a bitmap
+        #[ink(message)]
+        pub fn add_ons_of(&self, ticket_id: EventId) -> u32 {
+            self.ticket_add_ons.get(&ticket_id).unwrap_or(0)
+        }
+
+        /// Returns the add-ons of the ticket ID that are still to be redeemed as a bitmap
+        #[ink(message)]
+        pub fn unredeemed_add_ons_of(&self, ticket_id: EventId) -> u32 {
+            self.add_ons_of(ticket_id) & !self.redeemed_add_ons.get(&ticket_id).unwrap_or(0)
+        }
+
+        /// Buys the add-on for the caller's ticket ID, the transferred value must match its price
+        /// plus tax
+        #[ink(message, payable)]
+        pub fn buy_add_on(&mut self, ticket_id: EventId, add_on: AddOnId) -> Result<(), Error> {
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            self.ensure_ticket_owner(ticket_id)?;
+            let mut terms = self.add_ons.get(&add_on).ok_or(Error::UnknownAddOn)?;
+            let bit = 1u32 << add_on;
+            let add_ons = self.add_ons_of(ticket_id);
+            if add_ons & bit != 0 {
+                return Err(Error::TokenExists);
+            }
+            if terms.sold >= terms.supply {
+                return Err(Error::SoldOut);
+            }
+            let tax = self.tax_on(terms.price);
+            if self.env().transferred_value() != terms.price + tax {
+                return Err(Error::IncorrectPayment);
+            }
+            terms.sold += 1;
+            self.add_ons.insert(&add_on, &terms);
+            self.ticket_add_ons.insert(&ticket_id, &(add_ons | bit));
+            self.revenue += terms.price;
+            let buyer = self.env().caller();
+            self.record_ticket_payment(buyer, ticket_id, terms.price + tax);
+            let receipt = Receipt {
+                add_on: Some(add_on),
+                ..self.receipt(buyer, ticket_id, 0, terms.price, tax)
+            };
+            self.invoice(receipt);
+            Ok(())
+        }
+
+        /// Redeems the add-on of the ticket ID, called by a gate scanner at the experience
+        #[ink(message)]
+        pub fn check_in_add_on(
+            &mut self,
+            ticket_id: EventId,
+            add_on: AddOnId,
+        ) -> Result<(), Error> {
+            self.ensure_role(Role::GateScanner)?;
+            if add_on >= 32 || self.add_ons_of(ticket_id) & (1u32 << add_on) == 0 {
+                return Err(Error::UnknownAddOn);
+            }
+            let redeemed = self.redeemed_add_ons.get(&ticket_id).unwrap_or(0);
+            let bit = 1u32 << add_on;
+            if redeemed & bit != 0 {
+                return Err(Error::AddOnAlreadyUsed);
+            }
+            self.redeemed_add_ons.insert(&ticket_id, &(redeemed | bit));
+            Ok(())
+        }
+
+        /// Makes the unsold ticket ID a sponsorship slot, sold through the regular sale
+        /// and auctions at the price set with `set_price_override` or the winning bid
+        #[ink(message)]
+        pub fn set_sponsorship(
+            &mut self,
+            ticket_id: EventId,
+            sponsorship: Sponsorship,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            if self.exists(ticket_id) {
+                return Err(Error::TokenExists);
+            }
+            self.sponsorships.insert(&ticket_id, &sponsorship);
+            Ok(())
+        }
+
+        /// Returns the sponsorship slot the ticket ID stands for, if any
+        #[ink(message)]
+        pub fn sponsorship_of(&self, ticket_id: EventId) -> Option<Sponsorship> {
+            self.sponsorships.get(&ticket_id)
+        }
+
+        /// Fails if the ticket ID is a sponsorship slot that can't change hands yet
+        fn ensure_unlocked(&self, ticket_id: EventId) -> Result<(), Error> {
+            match self.sponsorships.get(&ticket_id) {
+                Some(sponsorship) if self.env().block_timestamp() < sponsorship.locked_until => {
+                    Err(Error::TransferLocked)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        /// Offers the caller's ticket ID to `to`, who has `valid_for` to accept it with
+        /// `accept_transfer`. Replaces any earlier offer of the ticket ID.
+        #[ink(message)]
+        pub fn offer_transfer(
+            &mut self,
+            ticket_id: EventId,
+            to: AccountId,
+            valid_for: Timestamp,
+        ) -> Result<(), Error> {
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            self.ensure_ticket_owner(ticket_id)?;
+            self.ensure_unused(ticket_id)?;
+            self.ensure_unlocked(ticket_id)?;
+            if valid_for == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            self.pending_transfers.insert(
+                &ticket_id,
+                &PendingTransfer {
+                    from: self.env().caller(),
+                    to,
+                    expires_at: self.env().block_timestamp().saturating_add(valid_for),
+                },
+            );
+            Ok(())
+        }
+
+        /// Withdraws the offered transfer of the caller's ticket ID. Anyone can remove an
+        /// offer that has expired.
+        #[ink(message)]
+        pub fn cancel_transfer(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            let pending = self
+                .pending_transfers
+                .get(&ticket_id)
+                .ok_or(Error::NoPendingTransfer)?;
+            if self.env().block_timestamp() < pending.expires_at {
+                self.ensure_ticket_owner(ticket_id)?;
+            }
+            self.pending_transfers.remove(&ticket_id);
+            Ok(())
+        }
+
+        /// Returns the offered transfer of the ticket ID, if made by its current holder
+        #[ink(message)]
+        pub fn pending_transfer_of(&self, ticket_id: EventId) -> Option<PendingTransfer> {
+            self.pending_transfers
+                .get(&ticket_id)
+                .filter(|pending| self.ticket_owner.get(&ticket_id) == Some(pending.from))
+        }
+
+        /// Accepts the ticket ID offered to the caller, moving it to them
+        #[ink(message)]
+        pub fn accept_transfer(&mut self, ticket_id: EventId) -> Result<(), Error> {
+            let pending = self
+                .pending_transfer_of(ticket_id)
+                .ok_or(Error::NoPendingTransfer)?;
+            if pending.to != self.env().caller() {
+                return Err(Error::NotApproved);
+            }
+            if self.env().block_timestamp() >= pending.expires_at {
+                return Err(Error::TransferExpired);
+            }
+            self.ensure_unallocated(pending.from, 1)?;
+            self.transfer(pending.from, pending.to, ticket_id, 1)
+        }
+
+        /// Revokes the lost ticket ID and reissues it as `new_ticket_id` to the holder's
+        /// new account, keeping its seat, face value, pass days and add-ons.
+        /// The records of the revoked ticket ID are kept for auditing.
+        #[ink(message)]
+        pub fn reissue_ticket(
+            &mut self,
+            old_ticket_id: EventId,
+            new_ticket_id: EventId,
+            holder: AccountId,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            let old_holder = self
+                .ticket_owner
+                .get(&old_ticket_id)
+                .ok_or(Error::TokenNotFound)?;
+            if old_holder == self.owner {
+                return Err(Error::NotAllowed);
+            }
+            if self.cancelled {
+                return Err(Error::EventCancelled);
+            }
+            self.ensure_unused(old_ticket_id)?;
+            if self.is_taken(new_ticket_id) {
+                return Err(Error::TokenExists);
+            }
+
+            let collected = self.ticket_payments.get(&old_ticket_id).unwrap_or(0);
+            self.move_balance(old_holder, holder, 1)?;
+            self.reduce_payment(old_holder, collected);
+            self.record_payment(holder, collected);
+            self.ticket_payments.remove(&old_ticket_id);
+            if collected > 0 {
+                self.ticket_payments.insert(&new_ticket_id, &collected);
+            }
+            self.ticket_owner.remove(&old_ticket_id);
+            self.ticket_owner.insert(&new_ticket_id, &holder);
+            self.index_ticket(new_ticket_id);
+            self.ticket_status
+                .insert(&old_ticket_id, &TicketStatus::Revoked);
+            self.log_change(ChangeKind::Revoke, old_ticket_id, old_holder);
+            #[cfg(feature = "resale")]
+            self.listings.remove(&old_ticket_id);
+            self.swap_requests.remove(&old_ticket_id);
+            self.pending_transfers.remove(&old_ticket_id);
+            if let Some(face_value) = self.face_values.get(&old_ticket_id) {
+                self.face_values.insert(&new_ticket_id, &face_value);
+            }
+            if let Some(original_buyer) = self.original_buyers.get(&old_ticket_id) {
+                self.original_buyers.insert(&new_ticket_id, &original_buyer);
+            }
+            if let Some(concession) = self.ticket_concession.get(&old_ticket_id) {
+                self.ticket_concession.insert(&new_ticket_id, &concession);
+            }
+            if let Some(days) = self.pass_days.get(&old_ticket_id) {
+                self.pass_days.insert(&new_ticket_id, &days);
+            }
+            if let Some(add_ons) = self.ticket_add_ons.get(&old_ticket_id) {
+                self.ticket_add_ons.insert(&new_ticket_id, &add_ons);
+            }
+            if let Some(redeemed) = self.redeemed_add_ons.get(&old_ticket_id) {
+                self.redeemed_add_ons.insert(&new_ticket_id, &redeemed);
+            }
+            if let Some(section) = self.ticket_section.get(&old_ticket_id) {
+                self.ticket_section.insert(&new_ticket_id, &section);
+            }
+            if let Some(sponsorship) = self.sponsorships.get(&old_ticket_id) {
+                self.sponsorships.insert(&new_ticket_id, &sponsorship);
+            }
+            if let Some(seat) = self.ticket_seats.get(&old_ticket_id) {
+                self.ticket_seats.remove(&old_ticket_id);
+                self.assign_seat(new_ticket_id, seat)?;
+            }
+            self.env().emit_event(TicketReissued {
+                old_ticket_id,
+                new_ticket_id,
+                holder,
+            });
+            Ok(())
+        }
+
+        /// Verifies up to `MAX_VERIFY_BATCH` ticket IDs against the accounts presenting them,
+        /// letting scanners pre-validate a group in a single call
+        #[ink(message)]
+        pub fn verify_batch(
+            &self,
+            tickets: Vec<(EventId, AccountId)>,
+        ) -> Result<Vec<VerifyResult>, Error> {
+            if tickets.len() > MAX_VERIFY_BATCH as usize {
+                return Err(Error::InvalidAmount);
+            }
+            Ok(tickets
+                .into_iter()
+                .map(|(ticket_id, account)| self.verify(ticket_id, account))
+                .collect())
+        }
+
+        /// Verifies the ticket ID against the account presenting it
+        fn verify(&self, ticket_id: EventId, account: AccountId) -> VerifyResult {
+            if self.cancelled {
+                return VerifyResult::Cancelled;
+            }
+            match self.ticket_status.get(&ticket_id) {
+                Some(TicketStatus::Refunded) => return VerifyResult::Refunded,
+                Some(TicketStatus::Revoked) => return VerifyResult::Revoked,
+                Some(TicketStatus::Withdrawn) => return VerifyResult::NotFound,
+                None => {}
+            }
+            match self.ticket_owner.get(&ticket_id) {
+                None => return VerifyResult::NotFound,
+                Some(holder) if holder != account => return VerifyResult::WrongHolder,
+                Some(_) => {}
+            }
+            let bit = match self.current_day() {
+                Some(day) => 1u32 << day,
+                None => return VerifyResult::NotValidToday,
+            };
+            if self.pass_days_of(ticket_id) & bit == 0 {
+                return VerifyResult::NotValidToday;
+            }
+            if self.checked_in_days.get(&ticket_id).unwrap_or(0) & bit != 0 {
+                VerifyResult::CheckedIn
+            } else {
+                VerifyResult::Valid
+            }
+        }
+
+        /// Adds a newly issued ticket ID to the index snapshots are built from
+        /// and logs it as minted to its holder
+        fn index_ticket(&mut self, ticket_id: EventId) {
+            self.issued_tickets.insert(&self.issued_count, &ticket_id);
+            self.issued_count += 1;
+            if let Some(holder) = self.ticket_owner.get(&ticket_id) {
+                self.log_change(ChangeKind::Mint, ticket_id, holder);
+            }
+        }
+
+        /// Returns the valid ticket IDs and their holders among the issued ticket IDs
+        /// `start` to `start + limit`, the canonical snapshot entries. `limit` is capped
+        /// at `MAX_SNAPSHOT_CHUNK`.
+        #[ink(message)]
+        pub fn snapshot_chunk(&self, start: u32, limit: u32) -> Vec<(EventId, AccountId)> {
+            let limit = limit.min(MAX_SNAPSHOT_CHUNK);
+            let end = self.issued_count.min(start.saturating_add(limit));
+            (start..end)
+                .filter_map(|index| self.issued_tickets.get(&index))
+                .filter(|ticket_id| !self.ticket_status.contains(ticket_id))
+                .filter_map(|ticket_id| {
+                    self.ticket_owner
+                        .get(&ticket_id)
+                        .map(|holder| (ticket_id, holder))
+                })
+                .collect()
+        }
+
+        /// Returns the amount of issued ticket IDs `snapshot_chunk` can page through
+        #[ink(message)]
+        pub fn get_issued_count(&self) -> u32 {
+            self.issued_count
+        }
+
+        /// Hashes up to `limit` more issued ticket IDs into the snapshot being built,
+        /// completing it once every issued ticket ID is hashed. `limit` is capped at
+        /// `MAX_SNAPSHOT_CHUNK`.
+        ///
+        /// The snapshot starts over if any change is logged between two calls, so the
+        /// committed hash always matches the holder list as of its `seq`.
+        ///
+        /// Returns the number of ticket IDs processed; zero means the snapshot is complete.
+        #[ink(message)]
+        pub fn build_snapshot(&mut self, limit: u32) -> Result<u32, Error> {
+            self.ensure_owner()?;
+            if !self.non_fungible {
+                return Err(Error::NonFungibleOnly);
+            }
+            if limit == 0 {
+                return Err(Error::InvalidAmount);
+            }
+            if self.snapshot_seq != self.change_count {
+                self.snapshot_cursor = 0;
+            }
+            if self.snapshot_cursor == 0 {
+                self.snapshot_hash = Hash::default();
+                self.snapshot_size = 0;
+                self.snapshot_seq = self.change_count;
+            }
+            let start = self.snapshot_cursor;
+            let limit = limit.min(MAX_SNAPSHOT_CHUNK);
+            let end = self.issued_count.min(start.saturating_add(limit));
+            let mut hash = self.snapshot_hash;
+            for entry in self.snapshot_chunk(start, end - start) {
+                let mut output = <Blake2x256 as HashOutput>::Type::default();
+                ink_env::hash_encoded::<Blake2x256, _>(&(hash, entry.0, entry.1), &mut output);
+                hash = Hash::from(output);
+                self.snapshot_size += 1;
+            }
+            self.snapshot_hash = hash;
+            if end == self.issued_count {
+                self.snapshot = Some(SnapshotCommitment {
+                    hash,
+                    size: self.snapshot_size,
+                    seq: self.snapshot_seq,
+                    timestamp: self.env().block_timestamp(),
+                });
+                self.snapshot_cursor = 0;
+            } else {
+                self.snapshot_cursor = end;
+            }
+            Ok(end - start)
+        }
+
+        /// Returns the latest completed snapshot of the holder list, if any
+        #[ink(message)]
+        pub fn get_snapshot(&self) -> Option<SnapshotCommitment> {
+            self.snapshot
+        }
+
+        /// Appends a change to a ticket ID to the change log
+        fn log_change(&mut self, kind: ChangeKind, ticket_id: EventId, account: AccountId) {
+            let seq = self.change_count;
+            self.changes.insert(
+                &seq,
+                &Change {
+                    seq,
+                    kind,
+                    ticket_id,
+                    account,
+                    block: self.env().block_number(),
+                },
+            );
+            self.change_count += 1;
+        }
+
+        /// Returns up to `limit` changes starting at sequence number `seq`, capped at
+        /// `MAX_CHANGES_PAGE`. Callers catch up by passing the last seen `seq` plus one.
+        #[ink(message)]
+        pub fn changes_since(&self, seq: u64, limit: u32) -> Vec<Change> {
+            let end = self
+                .change_count
+                .min(seq.saturating_add(u64::from(limit.min(MAX_CHANGES_PAGE))));
+            (seq..end)
+                .filter_map(|seq| self.changes.get(&seq))
+                .collect()
+        }
+
+        /// Returns the sequence number the next change will get
+        #[ink(message)]
+        pub fn get_change_count(&self) -> u64 {
+            self.change_count
+        }
+
+        /// Returns the corporate account of the company if its employees can still claim
+        fn open_corporate_account(&self, company: AccountId) -> Option<CorporateAccount> {
+            self.corporate_accounts
+                .get(&company)
+                .filter(|account| self.env().block_timestamp() < account.claim_deadline)
+        }
+
+        /// Returns an error if moving the tickets would leave the company with fewer
+        /// tickets than its employees may still claim
+        fn ensure_unallocated(&self, from: AccountId, tickets: Balance) -> Result<(), Error> {
+            if let Some(account) = self.open_corporate_account(from) {
+                let balance = self.balance.get(&from).unwrap_or(0);
+                if balance.saturating_sub(tickets) < account.allocated {
+                    return Err(Error::TicketsAllocated);
+                }
+            }
+            Ok(())
+        }
+
+        /// Makes the caller a corporate account whose employees can claim tickets from
+        /// it until `claim_deadline`. The deadline can only be extended, and once it has
+        /// passed the unclaimed allowances lapse and the tickets are the company's again.
+        #[ink(message)]
+        pub fn set_claim_deadline(&mut self, claim_deadline: Timestamp) -> Result<(), Error> {
+            let company = self.env().caller();
+            let now = self.env().block_timestamp();
+            if claim_deadline <= now {
+                return Err(Error::InvalidAmount);
+            }
+            let account = match self.corporate_accounts.get(&company) {
+                Some(account) if now >= account.claim_deadline => {
+                    return Err(Error::ClaimDeadlinePassed)
+                }
+                Some(account) if claim_deadline < account.claim_deadline => {
+                    return Err(Error::InvalidAmount)
+                }
+                Some(account) => CorporateAccount {
+                    claim_deadline,
+                    ..account
+                },
+                None => CorporateAccount {
+                    allocated: 0,
+                    claim_deadline,
+                },
+            };
+            self.corporate_accounts.insert(&company, &account);
+            Ok(())
+        }
+
+        /// Lets the employee claim `count` of the caller's tickets, replacing any earlier
+        /// allowance. The allowances together can't exceed the tickets the company holds.
+        #[ink(message)]
+        pub fn set_sub_allowance(
+            &mut self,
+            employee: AccountId,
+            count: Balance,
+        ) -> Result<(), Error> {
+            let company = self.env().caller();
+            let mut account = self
+                .corporate_accounts
+                .get(&company)
+                .ok_or(Error::NoCorporateAccount)?;
+            if self.env().block_timestamp() >= account.claim_deadline {
+                return Err(Error::ClaimDeadlinePassed);
+            }
+            let previous = self.sub_allowances.get(&(company, employee)).unwrap_or(0);
+            let allocated = account.allocated - previous + count;
+            if allocated > self.balance.get(&company).unwrap_or(0) {
+                return Err(Error::InsufficientBalance);
+            }
+            account.allocated = allocated;
+            self.corporate_accounts.insert(&company, &account);
+            if count == 0 {
+                self.sub_allowances.remove(&(company, employee));
+            } else {
+                self.sub_allowances.insert(&(company, employee), &count);
+            }
+            Ok(())
+        }
+
+        /// Returns the corporate account of the company, if any
+        #[ink(message)]
+        pub fn corporate_account_of(&self, company: AccountId) -> Option<CorporateAccount> {
+            self.corporate_accounts.get(&company)
+        }
+
+        /// Returns the tickets the employee may still claim from the company,
+        /// zero once the claim deadline has passed
+        #[ink(message)]
+        pub fn sub_allowance_of(&self, company: AccountId, employee: AccountId) -> Balance {
+            if self.open_corporate_account(company).is_none() {
+                return 0;
+            }
+            self.sub_allowances.get(&(company, employee)).unwrap_or(0)
+        }
+
+        /// Claims a ticket of the ticket ID from the company's block out of the caller's
+        /// allowance
+        #[ink(message)]
+        pub fn claim_corporate_ticket(
+            &mut self,
+            company: AccountId,
+            event_id: EventId,
+        ) -> Result<(), Error> {
+            let employee = self.env().caller();
+            let mut account = self
+                .corporate_accounts
+                .get(&company)
+                .ok_or(Error::NoCorporateAccount)?;
+            if self.env().block_timestamp() >= account.claim_deadline {
+                return Err(Error::ClaimDeadlinePassed);
+            }
+            let allowance = self.sub_allowances.get(&(company, employee)).unwrap_or(0);
+            if allowance == 0 {
+                return Err(Error::NoAllowance);
+            }
+
+            self.transfer(company, employee, event_id, 1)?;
+            account.allocated -= 1;
+            self.corporate_accounts.insert(&company, &account);
+            if allowance == 1 {
+                self.sub_allowances.remove(&(company, employee));
+            } else {
+                self.sub_allowances
+                    .insert(&(company, employee), &(allowance - 1));
+            }
+            Ok(())
+        }
+    }
 
     /// Unit tests
     #[cfg(test)]
@@ -213,121 +5324,3087 @@ mod ticket_event {
         /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
 
-        /// Imports `ink_lang` so we can use `#[ink::test]`.
-        use ink_lang as ink;
+        /// Imports `ink_lang` so we can use `#[ink::test]`.
+        use ink_lang as ink;
+
+        /// We test if the default constructor does its job.
+        #[ink::test]
+        fn create_event_works() {
+            let contract = TicketEvent::new(
+                100,
+                1337,
+                "Test_Name".to_string(),
+                "Test_Location".to_string(),
+                "Test_Symbol".to_string(),
+                "Test_Date".to_string(),
+                55,
+                Hash::from([0x42; 32]),
+            );
+            assert_eq!(contract.get_total_tickets(), 100);
+            assert_eq!(contract.get_name(), "Test_Name");
+            assert_eq!(contract.get_location(), "Test_Location");
+            assert_eq!(contract.owner(), AccountId::from([0x1; 32]));
+            assert_eq!(contract.get_symbol(), "Test_Symbol");
+            assert_eq!(contract.get_date(), "Test_Date");
+            assert_eq!(contract.get_price(), 55);
+            assert_eq!(contract.get_balance(), 100);
+        }
+
+        /// Testings minting of tickets
+        #[ink::test]
+        fn minting_tests() {
+            let mut contract = TicketEvent::new(
+                0,
+                1337,
+                "Test_Name".to_string(),
+                "Test_Location".to_string(),
+                "Test_Symbol".to_string(),
+                "Test_Date".to_string(),
+                55,
+                Hash::from([0x42; 32]),
+            );
+            contract.mint(1, 10).unwrap();
+            assert_eq!(contract.get_total_tickets(), 10);
+            assert_eq!(contract.get_balance(), 10);
+        }
+
+        /// Testing changing of ownership
+        #[ink::test]
+        fn ownership_tests() {
+            let mut contract = TicketEvent::new(
+                0,
+                1337,
+                "Test_Name".to_string(),
+                "Test_Location".to_string(),
+                "Test_Symbol".to_string(),
+                "Test_Date".to_string(),
+                55,
+                Hash::from([0x42; 32]),
+            );
+            contract.mint(1, 10).unwrap();
+            contract
+                .transfer_from(AccountId::from([0x1; 32]), AccountId::from([0x2; 32]), 1, 1)
+                .unwrap();
+            assert_eq!(contract.get_balance(), 9);
+            assert_eq!(contract.get_balance_of(AccountId::from([0x2; 32])), 1);
+            contract
+                .transfer_from(AccountId::from([0x1; 32]), AccountId::from([0x2; 32]), 1, 5)
+                .unwrap();
+            assert_eq!(contract.get_balance(), 4);
+            assert_eq!(contract.get_balance_of(AccountId::from([0x2; 32])), 6);
+        }
+
+        /// Testing transfering tickets with no ID should panic
+        #[ink::test]
+        #[should_panic(expected = "TokenNotFound")]
+        fn transfering_tests() {
+            let mut contract = TicketEvent::new(
+                0,
+                1337,
+                "Test_Name".to_string(),
+                "Test_Location".to_string(),
+                "Test_Symbol".to_string(),
+                "Test_Date".to_string(),
+                55,
+                Hash::from([0x42; 32]),
+            );
+            contract.mint(1, 10).unwrap();
+            contract
+                .transfer_from(AccountId::from([0x1; 32]), AccountId::from([0x2; 32]), 2, 1)
+                .unwrap();
+        }
+
+        /// Test removing token with ID from owner
+        #[ink::test]
+        fn remove_token_tests() {
+            let mut contract = TicketEvent::new(
+                0,
+                1337,
+                "Test_Name".to_string(),
+                "Test_Location".to_string(),
+                "Test_Symbol".to_string(),
+                "Test_Date".to_string(),
+                55,
+                Hash::from([0x42; 32]),
+            );
+            contract.mint(1, 10).unwrap();
+            contract.mint(2, 10).unwrap();
+            contract
+                .remove_token_from(AccountId::from([0x1; 32]), 1)
+                .unwrap();
+            assert_eq!(contract.get_balance(), 19);
+
+            contract
+                .transfer_from(AccountId::from([0x1; 32]), AccountId::from([0x2; 32]), 2, 5)
+                .unwrap();
+            assert_eq!(contract.get_balance(), 14);
+            assert_eq!(contract.total_tickets, 20);
+        }
+
+        /// Test the unlinked constructor leaves the sale closed
+        #[ink::test]
+        fn new_unlinked_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            assert_eq!(contract.owner(), AccountId::from([0x1; 32]));
+            assert_eq!(contract.get_name(), "");
+            assert_eq!(contract.get_total_tickets(), 0);
+            assert!(!contract.is_sale_open());
+            assert_eq!(contract.get_ticket_contract(), None);
+            assert_eq!(contract.mint(1, 1), Err(Error::SaleNotOpen));
+        }
+
+        /// Test configuring the event and opening the sale afterwards
+        #[ink::test]
+        fn configure_then_open_sale_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract
+                .set_event_details(
+                    "Test_Name".to_string(),
+                    "Test_Location".to_string(),
+                    "Test_Symbol".to_string(),
+                    "Test_Date".to_string(),
+                )
+                .unwrap();
+            contract.set_price(55).unwrap();
+            contract.set_total_tickets(100).unwrap();
+            contract.open_sale().unwrap();
+            assert_eq!(contract.get_name(), "Test_Name");
+            assert_eq!(contract.get_price(), 55);
+            assert_eq!(contract.get_balance(), 100);
+            assert_eq!(contract.set_total_tickets(5), Err(Error::SaleAlreadyOpen));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x2; 32]));
+            assert_eq!(contract.set_price(1), Err(Error::NotOwner));
+        }
+
+        /// Test sweeping unclaimed refunds after the claims deadline
+        #[ink::test]
+        fn sweep_unclaimed_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            let holder = AccountId::from([0x2; 32]);
+            contract.credit_refund(holder, 10).unwrap();
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                ink_env::test::callee::<ink_env::DefaultEnvironment>(),
+                100,
+            );
+
+            assert_eq!(
+                contract.set_claims_deadline(1000),
+                Err(Error::DeadlineTooSoon)
+            );
+            contract.set_claims_deadline(MIN_CLAIMS_PERIOD).unwrap();
+            assert_eq!(contract.sweep_unclaimed(), Err(Error::ClaimsStillOpen));
+
+            // Zero credits neither create ledger entries nor push the deadline back
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(DAY);
+            contract.credit_refund(holder, 0).unwrap();
+            assert_eq!(contract.refund_account_count, 1);
+
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(MIN_CLAIMS_PERIOD);
+            assert_eq!(contract.sweep_unclaimed(), Ok(10));
+            assert_eq!(contract.sweep_unclaimed(), Err(Error::ClaimsClosed));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(holder);
+            assert_eq!(contract.claim_refund(), Err(Error::ClaimsClosed));
+
+            // Credits after the sweep are paid out directly
+            contract.credit_refund(holder, 5).unwrap();
+            assert_eq!(contract.refund_of(holder), 0);
+            assert_eq!(
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(
+                    ink_env::test::callee::<ink_env::DefaultEnvironment>()
+                ),
+                Ok(85)
+            );
+        }
+
+        /// Test zero balances are removed and swept ledger entries can be cleaned up
+        #[ink::test]
+        fn cleanup_refund_ledger_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            let owner = AccountId::from([0x1; 32]);
+            contract.set_total_tickets(1).unwrap();
+            contract.open_sale().unwrap();
+            assert_eq!(contract.mint(0, 0), Err(Error::InvalidAmount));
+            contract
+                .transfer_from(owner, AccountId::from([0x3; 32]), 0, 0)
+                .unwrap();
+            assert_eq!(contract.balance.get(&AccountId::from([0x3; 32])), None);
+            contract
+                .transfer_from(owner, AccountId::from([0x2; 32]), 0, 1)
+                .unwrap();
+            assert_eq!(contract.balance.get(&owner), None);
+
+            contract
+                .credit_refund(AccountId::from([0x2; 32]), 5)
+                .unwrap();
+            contract
+                .credit_refund(AccountId::from([0x3; 32]), 5)
+                .unwrap();
+            assert_eq!(
+                contract.cleanup_refund_ledger(10),
+                Err(Error::ClaimsStillOpen)
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                ink_env::test::callee::<ink_env::DefaultEnvironment>(),
+                100,
+            );
+            contract.set_claims_deadline(MIN_CLAIMS_PERIOD).unwrap();
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(MIN_CLAIMS_PERIOD);
+            contract.sweep_unclaimed().unwrap();
+
+            assert_eq!(contract.cleanup_refund_ledger(1), Ok(1));
+            assert_eq!(contract.cleanup_refund_ledger(10), Ok(1));
+            assert_eq!(contract.cleanup_refund_ledger(10), Ok(0));
+            assert_eq!(contract.refund_of(AccountId::from([0x3; 32])), 0);
+            assert_eq!(contract.refunds.get(&AccountId::from([0x3; 32])), None);
+        }
+
+        /// Test attendee data can be cleared by the holder and purged after the event
+        #[ink::test]
+        fn attendee_data_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract.open_sale().unwrap();
+            contract.mint(1, 1).unwrap();
+            contract.mint(2, 1).unwrap();
+            contract
+                .set_attendee_data(1, Hash::from([0x11; 32]))
+                .unwrap();
+            contract
+                .set_attendee_data(2, Hash::from([0x22; 32]))
+                .unwrap();
+            assert_eq!(contract.get_attendee_data(1), Some(Hash::from([0x11; 32])));
+
+            contract.clear_attendee_data(1).unwrap();
+            assert_eq!(contract.get_attendee_data(1), None);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x2; 32]));
+            assert_eq!(contract.clear_attendee_data(2), Err(Error::NotOwner));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
+            contract.set_event_time(100).unwrap();
+            assert_eq!(contract.purge_attendee_data(10), Err(Error::EventNotOver));
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(100);
+            assert_eq!(contract.purge_attendee_data(10), Ok(2));
+            assert_eq!(contract.get_attendee_data(2), None);
+        }
+
+        /// Test transfers of several ticket IDs are checked in full before the hook is called
+        #[ink::test]
+        fn transfer_hook_per_ticket_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let buyer = AccountId::from([0x2; 32]);
+            let friend = AccountId::from([0x3; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+            contract
+                .register_hook(HookPoint::BeforeTransfer, AccountId::from([0x9; 32]), 1_000)
+                .unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            contract.buy_ticket(1, 2).unwrap();
+            // The hook is not reached while any of the ticket IDs can't be moved
+            assert_eq!(
+                contract.transfer_from(buyer, friend, 1, 3),
+                Err(Error::NotOwner)
+            );
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract.unregister_hook(HookPoint::BeforeTransfer).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            contract.transfer_from(buyer, friend, 1, 2).unwrap();
+            assert_eq!(contract.owner_of(1), Some(friend));
+            assert_eq!(contract.owner_of(2), Some(friend));
+            assert_eq!(contract.get_balance_of(friend), 2);
+        }
+
+        /// Test registering hooks within the gas budget
+        #[ink::test]
+        fn hook_registry_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            let hook = AccountId::from([0x9; 32]);
+            assert_eq!(contract.get_hook(HookPoint::BeforeTransfer), None);
+            assert_eq!(
+                contract.register_hook(HookPoint::BeforeTransfer, hook, MAX_HOOK_GAS + 1),
+                Err(Error::HookGasTooHigh)
+            );
+            contract
+                .register_hook(HookPoint::BeforeTransfer, hook, 1_000)
+                .unwrap();
+            assert_eq!(
+                contract.get_hook(HookPoint::BeforeTransfer),
+                Some(Hook {
+                    contract: hook,
+                    gas_limit: 1_000
+                })
+            );
+            assert_eq!(contract.get_hook(HookPoint::BeforeRefund), None);
+            contract.unregister_hook(HookPoint::BeforeTransfer).unwrap();
+            assert_eq!(contract.get_hook(HookPoint::BeforeTransfer), None);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x2; 32]));
+            assert_eq!(
+                contract.unregister_hook(HookPoint::BeforeRefund),
+                Err(Error::NotOwner)
+            );
+        }
+
+        /// Test the circuit breaker trips on too many mints in one block
+        #[ink::test]
+        fn circuit_breaker_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract.open_sale().unwrap();
+            contract.set_circuit_breaker(10, 0).unwrap();
+            contract.mint(1, 6).unwrap();
+            contract.mint(1, 5).unwrap();
+            assert!(contract.is_paused());
+            assert_eq!(contract.mint(1, 1), Err(Error::Paused));
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            assert_eq!(contract.mint(1, 1), Err(Error::Paused));
+            assert_eq!(
+                contract.transfer_from(
+                    AccountId::from([0x1; 32]),
+                    AccountId::from([0x2; 32]),
+                    1,
+                    1
+                ),
+                Err(Error::Paused)
+            );
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+
+            contract.reset_circuit_breaker().unwrap();
+            contract.mint(1, 5).unwrap();
+            assert_eq!(contract.get_balance(), 16);
+        }
+
+        /// Test buying tickets at the configured price
+        #[ink::test]
+        fn buy_ticket_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract.set_price(5).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+
+            let buyer = AccountId::from([0x2; 32]);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(9);
+            assert_eq!(contract.buy_ticket(1, 2), Err(Error::IncorrectPayment));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(10);
+            contract.buy_ticket(1, 2).unwrap();
+            assert_eq!(contract.get_balance_of(buyer), 2);
+            assert_eq!(contract.get_balance_of(AccountId::from([0x1; 32])), 8);
+            assert_eq!(contract.get_tickets_sold(), 2);
+            assert_eq!(contract.buy_ticket(1, 9), Err(Error::SoldOut));
+        }
+
+        /// Test the last-minute discount only applies close to an undersold event
+        #[ink::test]
+        fn last_minute_discount_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_event_time(1_000).unwrap();
+            contract.open_sale().unwrap();
+            assert_eq!(
+                contract.set_last_minute_discount(Some(LastMinuteDiscount {
+                    window: 100,
+                    sell_through_target_bps: 5_000,
+                    discount_bps: MAX_BPS + 1,
+                })),
+                Err(Error::InvalidBasisPoints)
+            );
+            contract
+                .set_last_minute_discount(Some(LastMinuteDiscount {
+                    window: 100,
+                    sell_through_target_bps: 5_000,
+                    discount_bps: 2_000,
+                }))
+                .unwrap();
+            assert_eq!(contract.active_discount_bps(), 0);
+
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(900);
+            assert_eq!(contract.active_discount_bps(), 2_000);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x2; 32]));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(400);
+            contract.buy_ticket(1, 5).unwrap();
+            assert_eq!(contract.active_discount_bps(), 0);
+        }
+
+        /// Test per-ticket price overrides take precedence over the ticket price
+        #[ink::test]
+        fn price_override_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract.set_price(10).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+            contract.set_price_override(7, Some(25)).unwrap();
+            assert_eq!(contract.price_override_of(7), Some(25));
+            assert_eq!(contract.price_override_of(8), None);
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(10);
+            assert_eq!(contract.buy_ticket(7, 1), Err(Error::IncorrectPayment));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(25);
+            contract.buy_ticket(7, 1).unwrap();
+
+            contract.set_price_override(7, None).unwrap();
+            assert_eq!(contract.price_override_of(7), None);
+        }
+
+        /// Test purchases are numbered sequentially
+        #[ink::test]
+        fn invoice_numbering_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract.set_price(10).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+
+            let buyer = AccountId::from([0x2; 32]);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(10);
+            contract.buy_ticket(1, 1).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(20);
+            contract.buy_ticket(2, 2).unwrap();
+
+            assert_eq!(contract.get_invoice_count(), 2);
+            assert_eq!(contract.get_receipt(0), None);
+            let receipt = contract.get_receipt(2).unwrap();
+            assert_eq!(receipt.buyer, buyer);
+            assert_eq!(receipt.ticket_id, 2);
+            assert_eq!(receipt.quantity, 2);
+            assert_eq!(receipt.price, 20);
+        }
+
+        /// Test projecting revenue from sold and remaining tickets
+        #[ink::test]
+        fn projected_revenue_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract.set_price(10).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+            assert_eq!(contract.projected_revenue(), (0, 100));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x2; 32]));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(30);
+            contract.buy_ticket(1, 3).unwrap();
+            assert_eq!(contract.projected_revenue(), (30, 70));
+        }
+
+        /// Test every ticket ID is unique in the non-fungible model
+        #[ink::test]
+        fn non_fungible_tickets_work() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let holder = AccountId::from([0x2; 32]);
+            contract.open_sale().unwrap();
+            assert_eq!(contract.ticket_model(), TicketModel::NonFungible);
+
+            contract.mint(10, 3).unwrap();
+            assert_eq!(contract.owner_of(12), Some(owner));
+            assert_eq!(contract.mint(12, 1), Err(Error::TokenExists));
+
+            contract.transfer_from(owner, holder, 11, 1).unwrap();
+            assert_eq!(contract.owner_of(10), Some(owner));
+            assert_eq!(contract.owner_of(11), Some(holder));
+            assert_eq!(contract.get_balance(), 2);
+            assert_eq!(contract.get_balance_of(holder), 1);
+            assert_eq!(
+                contract.transfer_from(owner, holder, 10, 2),
+                Err(Error::NotOwner)
+            );
+        }
+
+        /// Test quotes match what buying the tickets charges
+        #[ink::test]
+        fn get_quote_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract.set_price(10).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_price_override(3, Some(15)).unwrap();
+            contract.open_sale().unwrap();
+            assert_eq!(contract.get_quote(1, 2), Ok(20));
+            assert_eq!(contract.get_quote(3, 2), Ok(30));
+
+            let quote = contract.get_quote(3, 2).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(quote);
+            contract.buy_ticket(3, 2).unwrap();
+        }
+
+        /// Test tax is charged on top of the price and recorded separately
+        #[ink::test]
+        fn tax_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+            assert_eq!(
+                contract.set_tax(MAX_BPS + 1, "NO".to_string()),
+                Err(Error::InvalidBasisPoints)
+            );
+            contract.set_tax(2_500, "NO".to_string()).unwrap();
+            assert_eq!(contract.get_tax(), (2_500, "NO".to_string()));
+            assert_eq!(contract.get_quote(1, 2), Ok(250));
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(250);
+            contract.buy_ticket(1, 2).unwrap();
+            let receipt = contract.get_receipt(1).unwrap();
+            assert_eq!(receipt.price, 200);
+            assert_eq!(receipt.tax, 50);
+            assert_eq!(contract.get_tax_collected(), 50);
+            assert_eq!(contract.projected_revenue().0, 200);
+        }
+
+        /// Test every sale gets the next invoice number and is taxed
+        #[ink::test]
+        #[cfg(feature = "auctions")]
+        fn every_sale_is_invoiced_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_tax(1_000, "NL".to_string()).unwrap();
+            contract.set_voucher_terms(20, 10).unwrap();
+            contract.set_parking_terms(30, 10).unwrap();
+            contract.set_add_on(1, 50, 10).unwrap();
+            contract.open_sale().unwrap();
+            contract.start_auction(2, 100, 1).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(110);
+            contract.buy_ticket(1, 1).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(20);
+            assert_eq!(contract.buy_vouchers(1), Err(Error::IncorrectPayment));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(22);
+            contract.buy_vouchers(1).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(33);
+            contract.buy_parking(1).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(55);
+            contract.buy_add_on(1, 1).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(110);
+            contract.bid(2).unwrap();
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            contract.settle_auction(2).unwrap();
+
+            assert_eq!(contract.get_invoice_count(), 5);
+            let receipt = contract.get_receipt(2).unwrap();
+            assert_eq!((receipt.ticket_id, receipt.vouchers), (0, 1));
+            assert_eq!((receipt.price, receipt.tax), (20, 2));
+            assert_eq!(contract.get_receipt(3).unwrap().parking, 1);
+            let receipt = contract.get_receipt(4).unwrap();
+            assert_eq!((receipt.ticket_id, receipt.add_on), (1, Some(1)));
+            assert_eq!((receipt.price, receipt.tax), (50, 5));
+            let receipt = contract.get_receipt(5).unwrap();
+            assert_eq!((receipt.ticket_id, receipt.quantity), (2, 1));
+            assert_eq!((receipt.price, receipt.tax), (100, 10));
+            assert_eq!(contract.get_tax_collected(), 10 + 2 + 3 + 5 + 10);
+        }
+
+        /// Test buying tickets in a capped concession category
+        #[ink::test]
+        fn concession_tickets_work() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_concession(Concession::Child, 40, 2).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(80);
+            assert_eq!(
+                contract.buy_concession_ticket(1, 2, Concession::Senior),
+                Err(Error::ConcessionNotOffered)
+            );
+            contract
+                .buy_concession_ticket(1, 2, Concession::Child)
+                .unwrap();
+            assert_eq!(contract.concession_of(2), Some(Concession::Child));
+            assert_eq!(contract.get_concession(Concession::Child).unwrap().sold, 2);
+            assert_eq!(
+                contract.get_receipt(1).unwrap().concession,
+                Some(Concession::Child)
+            );
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(40);
+            assert_eq!(
+                contract.buy_concession_ticket(3, 1, Concession::Child),
+                Err(Error::SoldOut)
+            );
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_ticket(3, 1).unwrap();
+            assert_eq!(contract.concession_of(3), None);
+        }
+
+        /// Test carbon offsets are collected separately and withdrawn to the partner
+        #[ink::test]
+        fn carbon_offset_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            let partner = AccountId::from([0x5; 32]);
+            let options = PurchaseOptions {
+                carbon_offset: true,
+                ..PurchaseOptions::default()
+            };
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+            assert_eq!(
+                contract.get_quote_with_options(1, 2, options),
+                Err(Error::CarbonOffsetNotOffered)
+            );
+            contract.set_carbon_offset(3, Some(partner)).unwrap();
+            assert_eq!(contract.get_quote_with_options(1, 2, options), Ok(206));
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(206);
+            contract.buy_ticket_with_options(1, 2, options).unwrap();
+            assert_eq!(contract.get_receipt(1).unwrap().carbon_offset, 6);
+            assert_eq!(contract.get_carbon_offset_totals(), (6, 6));
+            assert_eq!(contract.projected_revenue().0, 200);
+            assert_eq!(
+                contract.set_carbon_offset(3, Some(AccountId::from([0x1; 32]))),
+                Err(Error::NotAllowed)
+            );
+            contract.set_carbon_offset(4, Some(partner)).unwrap();
+
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                ink_env::test::callee::<ink_env::DefaultEnvironment>(),
+                206,
+            );
+            assert_eq!(contract.withdraw_carbon_offset(), Ok(6));
+            assert_eq!(contract.get_carbon_offset_totals(), (6, 0));
+        }
+
+        /// Test merch vouchers bought with tickets and redeemed by a merch stand
+        #[ink::test]
+        fn merch_vouchers_work() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            let buyer = AccountId::from([0x2; 32]);
+            let merch_stand = AccountId::from([0x6; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_voucher_terms(20, 3).unwrap();
+            contract
+                .set_role(Role::MerchStand, merch_stand, true)
+                .unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            let options = PurchaseOptions {
+                vouchers: 2,
+                ..PurchaseOptions::default()
+            };
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(140);
+            contract.buy_ticket_with_options(1, 1, options).unwrap();
+            assert_eq!(contract.vouchers_of(buyer), 2);
+            assert_eq!(contract.buy_vouchers(2), Err(Error::SoldOut));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(20);
+            contract.buy_vouchers(1).unwrap();
+            assert_eq!(contract.get_voucher_terms(), (20, 3, 3));
+
+            assert_eq!(contract.redeem_voucher(buyer, 1), Err(Error::NotAllowed));
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(merch_stand);
+            contract.redeem_voucher(buyer, 3).unwrap();
+            assert_eq!(contract.vouchers_of(buyer), 0);
+            assert_eq!(
+                contract.redeem_voucher(buyer, 1),
+                Err(Error::InsufficientBalance)
+            );
+        }
+
+        /// Test parking passes are capped and used at the vehicle gate
+        #[ink::test]
+        fn parking_passes_work() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            let buyer = AccountId::from([0x2; 32]);
+            let scanner = AccountId::from([0x7; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_parking_terms(30, 2).unwrap();
+            contract
+                .set_role(Role::ParkingScanner, scanner, true)
+                .unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            let options = PurchaseOptions {
+                parking: 1,
+                ..PurchaseOptions::default()
+            };
+            assert_eq!(contract.get_quote_with_options(1, 1, options), Ok(130));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(130);
+            contract.buy_ticket_with_options(1, 1, options).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(60);
+            assert_eq!(contract.buy_parking(2), Err(Error::SoldOut));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(30);
+            contract.buy_parking(1).unwrap();
+            assert_eq!(contract.parking_passes_of(buyer), 2);
+            assert_eq!(contract.get_parking_terms(), (30, 2, 2));
+
+            assert_eq!(contract.check_in_vehicle(buyer), Err(Error::NotAllowed));
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(scanner);
+            contract.check_in_vehicle(buyer).unwrap();
+            assert_eq!(contract.parking_passes_of(buyer), 1);
+        }
+
+        /// Test food and drink credit spent at vendors and refunded after the event
+        #[ink::test]
+        fn food_and_drink_credit_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::Fungible);
+            let buyer = AccountId::from([0x2; 32]);
+            let vendor = AccountId::from([0x8; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_event_time(1_000).unwrap();
+            contract.set_role(Role::Vendor, vendor, true).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            let options = PurchaseOptions {
+                credit: 50,
+                ..PurchaseOptions::default()
+            };
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(150);
+            contract.buy_ticket_with_options(1, 1, options).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(25);
+            contract.top_up_credit().unwrap();
+            assert_eq!(contract.credit_of(buyer), 75);
+            assert_eq!(contract.refund_unspent_credit(), Err(Error::EventNotOver));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(vendor);
+            contract.spend_credit(buyer, 30).unwrap();
+            assert_eq!(
+                contract.spend_credit(buyer, 50),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(contract.get_credit_totals(), (45, 30));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(1_000);
+            assert_eq!(contract.refund_unspent_credit(), Ok(45));
+            assert_eq!(contract.credit_of(buyer), 0);
+            assert_eq!(contract.refund_of(buyer), 45);
+        }
+
+        /// Test festival passes can be checked in once on each of their days
+        #[ink::test]
+        fn festival_pass_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let scanner = AccountId::from([0x7; 32]);
+            contract.set_event_time(DAY).unwrap();
+            contract.set_role(Role::GateScanner, scanner, true).unwrap();
+            contract.open_sale().unwrap();
+            contract.mint(1, 2).unwrap();
+            contract.set_pass_days(1, 0b101).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(scanner);
+            assert_eq!(contract.check_in(1), Err(Error::NotValidToday));
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(DAY);
+            contract.check_in(1).unwrap();
+            contract.check_in(2).unwrap();
+            assert_eq!(
+                contract.check_in(1),
+                Err(Error::AlreadyCheckedIn(DAY, scanner))
+            );
+            assert_eq!(contract.remaining_days_of(1), 0b100);
+
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(2 * DAY);
+            assert_eq!(contract.check_in(1), Err(Error::NotValidToday));
+            assert_eq!(contract.check_in(2), Err(Error::NotValidToday));
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(3 * DAY);
+            contract.check_in(1).unwrap();
+            assert_eq!(contract.remaining_days_of(1), 0);
+        }
+
+        /// Test attendance is counted globally and per section
+        #[ink::test]
+        fn attendance_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let scanner = AccountId::from([0x7; 32]);
+            contract.set_event_time(0).unwrap();
+            contract.set_role(Role::GateScanner, scanner, true).unwrap();
+            contract.open_sale().unwrap();
+            contract.mint(1, 3).unwrap();
+            contract.set_ticket_section(3, 2).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(scanner);
+            contract.check_in(1).unwrap();
+            contract.check_in(3).unwrap();
+            assert_eq!(contract.current_attendance(), 2);
+            assert_eq!(contract.section_attendance(0), 1);
+            assert_eq!(contract.section_attendance(2), 1);
+
+            contract.check_out(3).unwrap();
+            assert_eq!(contract.check_out(3), Err(Error::NotCheckedIn));
+            assert_eq!(contract.current_attendance(), 1);
+            assert_eq!(contract.section_attendance(2), 0);
+
+            // Re-entry after checking out the same day
+            contract.check_in(3).unwrap();
+            assert_eq!(contract.current_attendance(), 2);
+            assert!(matches!(
+                contract.check_in(3),
+                Err(Error::AlreadyCheckedIn(_, _))
+            ));
+        }
+
+        /// Test checked in tickets can't be transferred
+        #[ink::test]
+        fn used_ticket_transfer_fails() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let scanner = AccountId::from([0x7; 32]);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_event_time(0).unwrap();
+            contract.set_role(Role::GateScanner, scanner, true).unwrap();
+            contract.open_sale().unwrap();
+            contract.mint(1, 2).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(scanner);
+            contract.check_in(1).unwrap();
+            assert_eq!(
+                contract.check_in_of(1).map(|record| record.scanner),
+                Some(scanner)
+            );
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            assert_eq!(
+                contract.transfer_from(owner, buyer, 1, 1),
+                Err(Error::TicketUsed)
+            );
+            assert_eq!(
+                contract.transfer_from(owner, buyer, 1, 2),
+                Err(Error::TicketUsed)
+            );
+            contract.transfer_from(owner, buyer, 2, 1).unwrap();
+            assert_eq!(contract.owner_of(1), Some(owner));
+        }
+
+        /// Test tickets never checked in can be returned after the event
+        #[ink::test]
+        fn unused_ticket_refund_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_price(100).unwrap();
+            contract
+                .set_unused_refund_policy(Some(UnusedRefundPolicy {
+                    refund_bps: 2_000,
+                    reserve_bps: 2_000,
+                    window: DAY,
+                }))
+                .unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_ticket(1, 1).unwrap();
+            assert_eq!(contract.get_unused_refund_reserve(), 20);
+            assert_eq!(contract.return_unused_ticket(1), Err(Error::EventNotOver));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
+            contract.set_event_time(DAY).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(DAY);
+            assert_eq!(contract.return_unused_ticket(1), Ok(20));
+            assert_eq!(contract.refund_of(buyer), 20);
+            assert_eq!(contract.payment_of(buyer), 0);
+            assert_eq!(contract.owner_of(1), None);
+            assert_eq!(contract.ticket_status(1), Some(TicketStatus::Refunded));
+            assert_eq!(
+                contract
+                    .changes_since(0, 10)
+                    .last()
+                    .map(|change| change.kind),
+                Some(ChangeKind::Refund)
+            );
+            assert_eq!(contract.return_unused_ticket(1), Err(Error::NotOwner));
+        }
+
+        /// Test a cancellation after a return refunds only the tickets still held
+        #[ink::test]
+        fn unused_ticket_refund_then_cancellation_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_price(100).unwrap();
+            contract
+                .set_unused_refund_policy(Some(UnusedRefundPolicy {
+                    refund_bps: 2_000,
+                    reserve_bps: 2_000,
+                    window: DAY,
+                }))
+                .unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_event_time(DAY).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            contract.buy_ticket(1, 2).unwrap();
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(DAY);
+            assert_eq!(contract.return_unused_ticket(1), Ok(20));
+            assert_eq!(contract.payment_of(buyer), 100);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract.cancel_event().unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            assert_eq!(contract.claim_cancellation_refund(), Ok(100));
+            assert_eq!(contract.refund_of(buyer), 120);
+        }
+
+        /// Test the original buyer gets a share of the resale premium
+        #[ink::test]
+        #[cfg(feature = "resale")]
+        fn resale_share_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let fan = AccountId::from([0x2; 32]);
+            let scalper = AccountId::from([0x3; 32]);
+            let buyer = AccountId::from([0x4; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_resale_share(5_000).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(fan);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_ticket(1, 1).unwrap();
+            contract.list_for_resale(1, 100).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(scalper);
+            contract.buy_resale(1).unwrap();
+            assert_eq!(contract.refund_of(fan), 100);
+            assert_eq!(contract.buy_resale(1), Err(Error::NotListed));
+            contract.list_for_resale(1, 300).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            assert_eq!(contract.buy_resale(1), Err(Error::IncorrectPayment));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(300);
+            contract.buy_resale(1).unwrap();
+            assert_eq!(contract.owner_of(1), Some(buyer));
+            assert_eq!(contract.refund_of(fan), 200);
+            assert_eq!(contract.refund_of(scalper), 200);
+        }
+
+        /// Test tickets can't be listed for resale below the price floor
+        #[ink::test]
+        #[cfg(feature = "resale")]
+        fn resale_floor_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            assert_eq!(
+                contract.set_resale_floor(MAX_BPS + 1),
+                Err(Error::InvalidBasisPoints)
+            );
+            contract.set_resale_floor(5_000).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x2; 32]));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_ticket(1, 1).unwrap();
+            assert_eq!(contract.resale_floor_of(1), 50);
+            assert_eq!(contract.list_for_resale(1, 49), Err(Error::BelowPriceFloor));
+            contract.list_for_resale(1, 50).unwrap();
+        }
+
+        /// Test late bids extend the auction
+        #[ink::test]
+        #[cfg(feature = "auctions")]
+        fn anti_sniping_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let alice = AccountId::from([0x2; 32]);
+            let bob = AccountId::from([0x3; 32]);
+            contract.set_total_tickets(10).unwrap();
+            contract
+                .set_anti_sniping(Some(AntiSniping {
+                    window: 2,
+                    extension: 3,
+                }))
+                .unwrap();
+            contract.start_auction(1, 100, 5).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.bid(1).unwrap();
+            assert_eq!(contract.get_auction(1).unwrap().end, 5);
+
+            for _ in 0..4 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            }
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            assert_eq!(contract.bid(1), Err(Error::BidTooLow));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(150);
+            contract.bid(1).unwrap();
+            assert_eq!(contract.get_auction(1).unwrap().end, 8);
+            assert_eq!(contract.refund_of(alice), 100);
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            assert_eq!(contract.settle_auction(1), Err(Error::AuctionNotEnded));
+            for _ in 0..3 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            }
+            contract.settle_auction(1).unwrap();
+            assert_eq!(contract.owner_of(1), Some(bob));
+        }
+
+        /// Test sealed bids are revealed and settled at the clearing price
+        #[ink::test]
+        #[cfg(feature = "auctions")]
+        fn sealed_bid_auction_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let alice = AccountId::from([0x2; 32]);
+            let bob = AccountId::from([0x3; 32]);
+            let carol = AccountId::from([0x4; 32]);
+            contract.set_total_tickets(10).unwrap();
+            contract.start_sealed_auction(1, 2, 50, 2, 2).unwrap();
+
+            let bids = [
+                (alice, 120, [0x2; 32]),
+                (bob, 80, [0x3; 32]),
+                (carol, 100, [0x4; 32]),
+            ];
+            for (bidder, bid, salt) in bids {
+                let commitment = contract.sealed_bid_commitment(bid, salt);
+                ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bidder);
+                ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(150);
+                contract.commit_bid(1, commitment).unwrap();
+            }
+            assert_eq!(
+                contract.reveal_bid(1, 100, [0x4; 32]),
+                Err(Error::BidNotRevealable)
+            );
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            assert_eq!(
+                contract.reveal_bid(1, 90, [0x4; 32]),
+                Err(Error::InvalidReveal)
+            );
+            for (bidder, bid, salt) in bids {
+                ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bidder);
+                contract.reveal_bid(1, bid, salt).unwrap();
+            }
+            assert_eq!(contract.refund_of(bob), 150);
+
+            assert_eq!(
+                contract.settle_sealed_auction(1),
+                Err(Error::AuctionNotEnded)
+            );
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            contract.settle_sealed_auction(1).unwrap();
+            assert_eq!(contract.owner_of(1), Some(alice));
+            assert_eq!(contract.owner_of(2), Some(carol));
+            assert_eq!(contract.refund_of(alice), 30 + 20);
+            assert_eq!(contract.refund_of(carol), 50);
+            assert_eq!(contract.get_sealed_auction(1), None);
+        }
+
+        /// Test refunds of a cancelled event go to the holders, in chunks
+        #[ink::test]
+        fn process_refunds_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_voucher_terms(20, 10).unwrap();
+            contract.set_parking_terms(30, 10).unwrap();
+            contract.set_tax(1_000, "NL".into()).unwrap();
+            contract.open_sale().unwrap();
+
+            let alice = AccountId::from([0x2; 32]);
+            let bob = AccountId::from([0x3; 32]);
+            let carol = AccountId::from([0x5; 32]);
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(110);
+            for (ticket_id, byte) in [(1, 0x2), (3, 0x4)] {
+                ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from(
+                    [byte; 32],
+                ));
+                contract.buy_ticket(ticket_id, 1).unwrap();
+            }
+            // 110 for the ticket, 20 for a voucher and 30 for parking with tax, and 10 credit
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(175);
+            let options = PurchaseOptions {
+                vouchers: 1,
+                parking: 1,
+                credit: 10,
+                ..PurchaseOptions::default()
+            };
+            contract.buy_ticket_with_options(2, 1, options).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            contract.transfer_from(alice, carol, 1, 1).unwrap();
+            assert_eq!(contract.payment_of(alice), 0);
+            assert_eq!(contract.payment_of(carol), 110);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            assert_eq!(contract.process_refunds(2), Err(Error::NotAllowed));
+            contract.cancel_event().unwrap();
+            assert_eq!(contract.open_sale(), Err(Error::EventCancelled));
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(carol);
+            assert_eq!(
+                contract.transfer_from(carol, alice, 1, 1),
+                Err(Error::EventCancelled)
+            );
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            assert_eq!(contract.process_refunds(2), Ok(2));
+            assert_eq!(contract.refund_of(alice), 0);
+            assert_eq!(contract.refund_of(AccountId::from([0x4; 32])), 110);
+            assert_eq!(contract.refund_of(bob), 0);
+            assert_eq!(contract.process_refunds(2), Ok(2));
+            assert_eq!(contract.process_refunds(2), Ok(0));
+            assert_eq!(contract.refund_of(bob), 175);
+            assert_eq!(contract.credit_of(bob), 0);
+            assert_eq!(contract.refund_of(carol), 110);
+            assert_eq!(contract.payment_of(AccountId::from([0x4; 32])), 0);
+        }
+
+        /// Test queued purchases are fulfilled in arrival order
+        #[ink::test]
+        fn purchase_queue_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let alice = AccountId::from([0x2; 32]);
+            let bob = AccountId::from([0x3; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(1).unwrap();
+            contract.set_queue_mode(true).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            assert_eq!(contract.buy_ticket(1, 1), Err(Error::QueueOnly));
+            assert_eq!(contract.enqueue_purchase(1, 0), Err(Error::InvalidAmount));
+            assert_eq!(contract.enqueue_purchase(1, 1), Ok(0));
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            assert_eq!(contract.enqueue_purchase(2, 1), Ok(1));
+            assert_eq!(contract.queue_length(), 2);
+
+            assert_eq!(contract.crank_queue(5), Ok(2));
+            assert_eq!(contract.queue_length(), 0);
+            assert_eq!(contract.owner_of(1), Some(alice));
+            assert_eq!(contract.owner_of(2), None);
+            assert_eq!(contract.refund_of(bob), 100);
+        }
+
+        /// Test purchases above the per-block throttle fail until the next block
+        #[ink::test]
+        fn purchase_throttle_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_purchase_throttle(2).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            contract.buy_ticket(1, 2).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            assert_eq!(contract.buy_ticket(3, 1), Err(Error::ThrottleExceeded));
+            assert!(!contract.is_paused());
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            contract.buy_ticket(3, 1).unwrap();
+        }
+
+        /// Test holders can refund themselves when the owner goes silent before the event
+        #[ink::test]
+        fn dead_man_switch_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_event_time(10 * DAY).unwrap();
+            contract.set_dead_man_period(Some(3 * DAY)).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_ticket(1, 1).unwrap();
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(2 * DAY);
+            assert_eq!(contract.refund_abandoned(), Err(Error::NotAllowed));
+
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(3 * DAY);
+            assert!(contract.is_abandoned());
+            assert_eq!(contract.refund_abandoned(), Ok(100));
+            assert_eq!(contract.refund_of(buyer), 100);
+            assert_eq!(contract.refund_abandoned(), Err(Error::NothingToClaim));
+            assert!(contract.is_cancelled());
+        }
+
+        /// Test emergency withdrawals wait for the timelock and can be vetoed
+        #[ink::test]
+        #[cfg(feature = "governance")]
+        fn emergency_withdraw_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let guardian = AccountId::from([0x9; 32]);
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                ink_env::test::callee::<ink_env::DefaultEnvironment>(),
+                100,
+            );
+            assert_eq!(
+                contract.announce_emergency_withdraw(owner, 10),
+                Err(Error::NoGuardian)
+            );
+            contract.set_guardian(guardian).unwrap();
+            assert_eq!(contract.set_guardian(owner), Err(Error::NotGuardian));
+            contract.credit_refund(guardian, 80).unwrap();
+            contract.add_credit(guardian, 5);
+            assert_eq!(
+                contract.announce_emergency_withdraw(owner, 16),
+                Err(Error::InsufficientBalance)
+            );
+
+            contract.announce_emergency_withdraw(owner, 10).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(guardian);
+            contract.cancel_emergency_withdraw().unwrap();
+            assert_eq!(contract.get_pending_withdrawal(), None);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract.announce_emergency_withdraw(owner, 10).unwrap();
+            assert_eq!(contract.emergency_withdraw(), Err(Error::TimelockActive));
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(
+                EMERGENCY_WITHDRAWAL_DELAY,
+            );
+            assert_eq!(contract.emergency_withdraw(), Ok(10));
+            assert_eq!(
+                contract.emergency_withdraw(),
+                Err(Error::NoPendingWithdrawal)
+            );
+        }
+
+        /// Test holders can refund themselves once the event is cancelled
+        #[ink::test]
+        fn cancellation_refund_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            assert_eq!(contract.trigger_refund_condition(), Err(Error::NotAllowed));
+            assert_eq!(
+                contract.set_refund_condition(Some(RefundCondition {
+                    oracle: AccountId::from([0x8; 32]),
+                    from: DAY,
+                    until: DAY,
+                })),
+                Err(Error::InvalidAmount)
+            );
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_ticket(1, 1).unwrap();
+            assert_eq!(contract.claim_cancellation_refund(), Err(Error::NotAllowed));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
+            contract.cancel_event().unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            assert_eq!(contract.claim_cancellation_refund(), Ok(100));
+            assert_eq!(contract.refund_of(buyer), 100);
+        }
+
+        /// Test group bookings get adjacent seats where available
+        #[ink::test]
+        fn adjacent_seats_work() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract
+                .set_section_layout(
+                    1,
+                    SectionLayout {
+                        rows: 2,
+                        seats_per_row: 3,
+                    },
+                )
+                .unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            assert_eq!(
+                contract.buy_seated_tickets(1, 2, 2, false),
+                Err(Error::UnknownSection)
+            );
+            assert_eq!(contract.buy_seated_tickets(1, 2, 1, false), Ok(true));
+            assert_eq!(contract.buy_seated_tickets(3, 2, 1, false), Ok(true));
+            assert_eq!(contract.section_of(4), 1);
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            assert_eq!(
+                contract.buy_seated_tickets(5, 2, 1, false),
+                Err(Error::NoAdjacentSeats)
+            );
+            assert_eq!(contract.buy_seated_tickets(5, 2, 1, true), Ok(false));
+        }
+
+        /// Test holders can swap seats through the swap board
+        #[ink::test]
+        fn seat_swap_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let alice = AccountId::from([0x2; 32]);
+            let bob = AccountId::from([0x3; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract
+                .set_section_layout(
+                    1,
+                    SectionLayout {
+                        rows: 3,
+                        seats_per_row: 1,
+                    },
+                )
+                .unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            contract.buy_seated_tickets(1, 1, 1, false).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            contract.buy_seated_tickets(2, 1, 1, false).unwrap();
+            contract.buy_seated_tickets(3, 1, 1, false).unwrap();
+            contract.request_seat_swap(3, 1, 1, 1).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            assert_eq!(contract.accept_seat_swap(1, 2), Err(Error::NoSwapRequest));
+            contract.accept_seat_swap(1, 3).unwrap();
+            assert_eq!(contract.get_seat_swap(3), None);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            contract.request_seat_swap(2, 1, 1, 1).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            assert_eq!(contract.accept_seat_swap(1, 2), Err(Error::SeatNotWanted));
+        }
+
+        /// Test holders bid to move their ticket ID to a released seat
+        #[ink::test]
+        #[cfg(feature = "auctions")]
+        fn upgrade_auction_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let alice = AccountId::from([0x2; 32]);
+            let bob = AccountId::from([0x3; 32]);
+            let front = Seat {
+                section: 1,
+                row: 1,
+                number: 1,
+            };
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract
+                .set_section_layout(
+                    1,
+                    SectionLayout {
+                        rows: 3,
+                        seats_per_row: 1,
+                    },
+                )
+                .unwrap();
+            contract.open_sale().unwrap();
+            contract.start_upgrade_auction(front, 10, 2).unwrap();
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            contract.buy_seated_tickets(1, 1, 1, false).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            contract.buy_seated_tickets(2, 1, 1, false).unwrap();
+            assert_eq!(contract.bid_upgrade(front, 1), Err(Error::NotOwner));
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(20);
+            contract.bid_upgrade(front, 2).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(30);
+            contract.bid_upgrade(front, 1).unwrap();
+            assert_eq!(contract.refund_of(bob), 20);
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            assert_eq!(
+                contract.buy_seated_tickets(3, 1, 1, false),
+                Err(Error::SoldOut)
+            );
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            contract.settle_upgrade_auction(front).unwrap();
+            assert_eq!(contract.get_upgrade_auction(front), None);
+            // The old seat of the upgraded ticket ID is back on sale
+            assert_eq!(contract.buy_seated_tickets(3, 1, 1, false), Ok(true));
+        }
+
+        /// Test a capacity reduction refunds the tickets of a section after its timelock
+        #[ink::test]
+        #[cfg(feature = "governance")]
+        fn capacity_reduction_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract
+                .set_section_layout(
+                    1,
+                    SectionLayout {
+                        rows: 1,
+                        seats_per_row: 2,
+                    },
+                )
+                .unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_seated_tickets(1, 1, 1, false).unwrap();
+            contract.buy_ticket(2, 1).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract
+                .announce_capacity_reduction(ReductionTarget::Section(1))
+                .unwrap();
+            assert_eq!(
+                contract.execute_capacity_reduction(),
+                Err(Error::TimelockActive)
+            );
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(
+                CAPACITY_REDUCTION_DELAY,
+            );
+            assert_eq!(contract.execute_capacity_reduction(), Ok(1));
+            assert_eq!(contract.ticket_status(1), Some(TicketStatus::Refunded));
+            assert_eq!(contract.owner_of(1), None);
+            assert_eq!(contract.owner_of(2), Some(buyer));
+            assert_eq!(contract.refund_of(buyer), 100);
+            // The free seat of the section is withdrawn along with the refunded one
+            assert_eq!(contract.get_total_tickets(), 8);
+            assert_eq!(contract.mint(1, 1), Err(Error::TokenExists));
+            #[cfg(feature = "auctions")]
+            assert_eq!(
+                contract.start_upgrade_auction(
+                    Seat {
+                        section: 1,
+                        row: 1,
+                        number: 1,
+                    },
+                    10,
+                    5
+                ),
+                Err(Error::SeatRetired)
+            );
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            assert_eq!(contract.buy_ticket(1, 1), Err(Error::TokenExists));
+            assert_eq!(
+                contract.buy_seated_tickets(3, 1, 1, true),
+                Err(Error::SoldOut)
+            );
+        }
+
+        /// Test a capacity reduction of ticket IDs burns the owner's and withdraws the unsold
+        #[ink::test]
+        #[cfg(feature = "governance")]
+        fn capacity_reduction_of_ticket_ids_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+            contract.mint(2, 1).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_ticket(1, 1).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract
+                .announce_capacity_reduction(ReductionTarget::TicketIds { first: 1, count: 3 })
+                .unwrap();
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(
+                CAPACITY_REDUCTION_DELAY,
+            );
+            assert_eq!(contract.execute_capacity_reduction(), Ok(1));
+            assert_eq!(contract.refund_of(buyer), 100);
+            assert_eq!(contract.owner_of(2), None);
+            assert_eq!(contract.ticket_status(2), Some(TicketStatus::Refunded));
+            assert_eq!(contract.ticket_status(3), Some(TicketStatus::Withdrawn));
+            assert_eq!(contract.get_total_tickets(), 8);
+            assert_eq!(contract.get_balance_of(owner), 8);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            for ticket_id in 1..=3 {
+                assert_eq!(contract.buy_ticket(ticket_id, 1), Err(Error::TokenExists));
+            }
+            contract.buy_ticket(4, 1).unwrap();
+        }
+
+        /// Test add-ons are bought per ticket ID and redeemed separately from entry
+        #[ink::test]
+        fn add_ons_work() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let buyer = AccountId::from([0x2; 32]);
+            let scanner = AccountId::from([0x7; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_add_on(3, 50, 1).unwrap();
+            contract.set_role(Role::GateScanner, scanner, true).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_ticket(1, 1).unwrap();
+            contract.buy_ticket(2, 1).unwrap();
+            assert_eq!(contract.buy_add_on(1, 3), Err(Error::IncorrectPayment));
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(50);
+            contract.buy_add_on(1, 3).unwrap();
+            assert_eq!(contract.buy_add_on(2, 3), Err(Error::SoldOut));
+            assert_eq!(contract.add_ons_of(1), 0b1000);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(scanner);
+            assert_eq!(contract.check_in_add_on(2, 3), Err(Error::UnknownAddOn));
+            contract.check_in_add_on(1, 3).unwrap();
+            assert_eq!(contract.check_in_add_on(1, 3), Err(Error::AddOnAlreadyUsed));
+            assert_eq!(contract.unredeemed_add_ons_of(1), 0);
+            assert_eq!(contract.check_in_of(1), None);
+        }
+
+        /// Test sponsorship slots are sold like tickets and locked until the event ends
+        #[ink::test]
+        fn sponsorship_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let sponsor = AccountId::from([0x2; 32]);
+            let other = AccountId::from([0x3; 32]);
+            contract.set_total_tickets(10).unwrap();
+            contract
+                .set_sponsorship(
+                    7,
+                    Sponsorship {
+                        entitlement: String::from("Main stage banner"),
+                        locked_until: DAY,
+                    },
+                )
+                .unwrap();
+            contract.set_price_override(7, Some(1_000)).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(sponsor);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(1_000);
+            contract.buy_ticket(7, 1).unwrap();
+            assert_eq!(
+                contract.sponsorship_of(7).unwrap().entitlement,
+                "Main stage banner"
+            );
+            assert_eq!(
+                contract.transfer_from(sponsor, other, 7, 1),
+                Err(Error::TransferLocked)
+            );
+            #[cfg(feature = "resale")]
+            assert_eq!(
+                contract.list_for_resale(7, 1_000),
+                Err(Error::TransferLocked)
+            );
+
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(DAY);
+            contract.transfer_from(sponsor, other, 7, 1).unwrap();
+        }
+
+        /// Test offered transfers only move once accepted before they expire
+        #[ink::test]
+        fn two_step_transfer_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let alice = AccountId::from([0x2; 32]);
+            let bob = AccountId::from([0x3; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            contract.buy_ticket(1, 2).unwrap();
+            contract.offer_transfer(1, bob, DAY).unwrap();
+            contract.offer_transfer(2, bob, DAY).unwrap();
+            assert_eq!(contract.accept_transfer(1), Err(Error::NotApproved));
+            contract.cancel_transfer(2).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            assert_eq!(contract.accept_transfer(2), Err(Error::NoPendingTransfer));
+            contract.accept_transfer(1).unwrap();
+            assert_eq!(contract.owner_of(1), Some(bob));
+            assert_eq!(contract.pending_transfer_of(1), None);
+            assert_eq!(contract.pending_transfers.get(&1), None);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            contract.offer_transfer(2, bob, DAY).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(DAY);
+            assert_eq!(contract.accept_transfer(2), Err(Error::TransferExpired));
+            // Anyone can clear an expired offer
+            contract.cancel_transfer(2).unwrap();
+            assert_eq!(contract.pending_transfers.get(&2), None);
+        }
+
+        /// Test a lost ticket ID is revoked and reissued with the same seat
+        #[ink::test]
+        fn reissue_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let lost = AccountId::from([0x2; 32]);
+            let recovered = AccountId::from([0x3; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract
+                .set_section_layout(
+                    1,
+                    SectionLayout {
+                        rows: 1,
+                        seats_per_row: 2,
+                    },
+                )
+                .unwrap();
+            contract
+                .set_sponsorship(
+                    1,
+                    Sponsorship {
+                        entitlement: String::from("Backstage pass"),
+                        locked_until: DAY,
+                    },
+                )
+                .unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(lost);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_seated_tickets(1, 1, 1, false).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
+            assert_eq!(
+                contract.reissue_ticket(1, 1, recovered),
+                Err(Error::TokenExists)
+            );
+            contract.reissue_ticket(1, 9, recovered).unwrap();
+            assert_eq!(contract.ticket_status(1), Some(TicketStatus::Revoked));
+            assert_eq!(contract.owner_of(1), None);
+            assert_eq!(contract.owner_of(9), Some(recovered));
+            assert_eq!(contract.get_balance_of(lost), 0);
+            assert_eq!(contract.section_of(9), 1);
+            assert_eq!(
+                contract.reissue_ticket(1, 10, recovered),
+                Err(Error::TokenNotFound)
+            );
+            // The revoked ticket ID can't be minted or sold again
+            assert_eq!(contract.mint(1, 1), Err(Error::TokenExists));
+
+            // The sponsorship and its transfer lock move with the reissued ticket ID
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(recovered);
+            assert_eq!(
+                contract.transfer_from(recovered, lost, 9, 1),
+                Err(Error::TransferLocked)
+            );
+        }
+
+        /// Test seats and ticket IDs resolve in both directions
+        #[ink::test]
+        fn seat_lookup_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract
+                .set_section_layout(
+                    4,
+                    SectionLayout {
+                        rows: 2,
+                        seats_per_row: 1,
+                    },
+                )
+                .unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            contract.buy_seated_tickets(5, 2, 4, true).unwrap();
+            let seat = Seat {
+                section: 4,
+                row: 2,
+                number: 1,
+            };
+            assert_eq!(contract.seat_of(6), Some(seat));
+            assert_eq!(contract.ticket_at(4, 2, 1), Some(6));
+            assert_eq!(contract.ticket_at(4, 3, 1), None);
+            assert_eq!(contract.seat_of(7), None);
+        }
+
+        /// Test a group of ticket IDs is verified in one call
+        #[ink::test]
+        fn verify_batch_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let scanner = AccountId::from([0x7; 32]);
+            let other = AccountId::from([0x2; 32]);
+            contract.set_event_time(0).unwrap();
+            contract.set_role(Role::GateScanner, scanner, true).unwrap();
+            contract.open_sale().unwrap();
+            contract.mint(1, 4).unwrap();
+            contract.set_pass_days(4, 0b10).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(scanner);
+            contract.check_in(2).unwrap();
+            assert_eq!(
+                contract.verify_batch(vec![
+                    (1, owner),
+                    (2, owner),
+                    (3, other),
+                    (4, owner),
+                    (5, owner)
+                ]),
+                Ok(vec![
+                    VerifyResult::Valid,
+                    VerifyResult::CheckedIn,
+                    VerifyResult::WrongHolder,
+                    VerifyResult::NotValidToday,
+                    VerifyResult::NotFound,
+                ])
+            );
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(DAY);
+            assert_eq!(
+                contract.verify_batch(vec![(4, owner)]),
+                Ok(vec![VerifyResult::Valid])
+            );
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(32 * DAY);
+            assert_eq!(
+                contract.verify_batch(vec![(1, owner)]),
+                Ok(vec![VerifyResult::NotValidToday])
+            );
+
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(0);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract.cancel_event().unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(scanner);
+            assert_eq!(
+                contract.verify_batch(vec![(1, owner)]),
+                Ok(vec![VerifyResult::Cancelled])
+            );
+            assert_eq!(contract.check_in(1), Err(Error::EventCancelled));
+            assert_eq!(
+                contract.verify_batch(vec![(1, owner); MAX_VERIFY_BATCH as usize + 1]),
+                Err(Error::InvalidAmount)
+            );
+        }
+
+        /// Test the holder list snapshot is built in chunks and matches its entries
+        #[ink::test]
+        fn snapshot_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            contract.open_sale().unwrap();
+            contract.mint(1, 3).unwrap();
+
+            assert_eq!(contract.build_snapshot(0), Err(Error::InvalidAmount));
+            assert_eq!(contract.build_snapshot(2), Ok(2));
+            assert_eq!(contract.get_snapshot(), None);
+            // A transfer between chunks starts the snapshot over
+            contract
+                .transfer_from(owner, AccountId::from([0x2; 32]), 1, 1)
+                .unwrap();
+            assert_eq!(contract.build_snapshot(2), Ok(2));
+            assert_eq!(contract.build_snapshot(2), Ok(1));
+
+            let mut hash = Hash::default();
+            for entry in contract.snapshot_chunk(0, 10) {
+                let mut output = <Blake2x256 as HashOutput>::Type::default();
+                ink_env::hash_encoded::<Blake2x256, _>(&(hash, entry.0, entry.1), &mut output);
+                hash = Hash::from(output);
+            }
+            let snapshot = contract.get_snapshot().unwrap();
+            assert_eq!(snapshot.hash, hash);
+            assert_eq!(snapshot.size, 3);
+            assert_eq!(snapshot.seq, contract.change_count);
+            assert_eq!(contract.snapshot_chunk(2, 10), vec![(3, owner)]);
+        }
+
+        /// Test changes to ticket IDs are logged in order
+        #[ink::test]
+        fn changes_since_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let scanner = AccountId::from([0x7; 32]);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_event_time(0).unwrap();
+            contract.set_role(Role::GateScanner, scanner, true).unwrap();
+            contract.open_sale().unwrap();
+            contract.mint(1, 2).unwrap();
+            contract.transfer_from(owner, buyer, 2, 1).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(scanner);
+            contract.check_in(2).unwrap();
+
+            let kinds: Vec<(ChangeKind, EventId, AccountId)> = contract
+                .changes_since(0, 10)
+                .iter()
+                .map(|change| (change.kind, change.ticket_id, change.account))
+                .collect();
+            assert_eq!(
+                kinds,
+                vec![
+                    (ChangeKind::Mint, 1, owner),
+                    (ChangeKind::Mint, 2, owner),
+                    (ChangeKind::Transfer, 2, buyer),
+                    (ChangeKind::CheckIn, 2, scanner),
+                ]
+            );
+            assert_eq!(contract.changes_since(3, 10).len(), 1);
+            assert_eq!(contract.changes_since(3, 10)[0].seq, 3);
+            assert!(contract.changes_since(4, 10).is_empty());
+        }
+
+        /// Test only the owner mints and proceeds owed to holders stay in the contract
+        #[ink::test]
+        fn withdraw_proceeds_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let buyer = AccountId::from([0x2; 32]);
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            assert_eq!(contract.mint(1, 1), Err(Error::NotOwner));
+            assert_eq!(contract.withdraw_proceeds(10), Err(Error::NotOwner));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from([0x1; 32]));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                ink_env::test::callee::<ink_env::DefaultEnvironment>(),
+                100,
+            );
+            contract.credit_refund(buyer, 30).unwrap();
+            assert_eq!(contract.withdrawable_proceeds(), 70);
+            assert_eq!(contract.withdraw_proceeds(70), Err(Error::EventNotOver));
+            contract.set_event_time(0).unwrap();
+            assert_eq!(
+                contract.withdraw_proceeds(71),
+                Err(Error::InsufficientBalance)
+            );
+            contract.withdraw_proceeds(70).unwrap();
+            assert_eq!(contract.withdrawable_proceeds(), 0);
+        }
+
+        /// Test only the holder or an approved operator moves the holder's tickets
+        #[ink::test]
+        fn transfer_approval_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let operator = AccountId::from([0x2; 32]);
+            let buyer = AccountId::from([0x3; 32]);
+            contract.open_sale().unwrap();
+            contract.mint(1, 2).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(operator);
+            assert_eq!(
+                contract.transfer_from(owner, buyer, 1, 1),
+                Err(Error::NotApproved)
+            );
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract.set_approval_for_all(operator, true);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(operator);
+            contract.transfer_from(owner, buyer, 1, 1).unwrap();
+            assert_eq!(contract.owner_of(1), Some(buyer));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract.set_approval_for_all(operator, false);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(operator);
+            assert_eq!(
+                contract.transfer_from(owner, buyer, 2, 1),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(
+                contract.remove_token_from(operator, 2),
+                Err(Error::InsufficientBalance)
+            );
+        }
+
+        /// Test employees claim tickets from the company's block until the deadline
+        #[ink::test]
+        fn corporate_allowances_work() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let company = AccountId::from([0x2; 32]);
+            let alice = AccountId::from([0x3; 32]);
+            let bob = AccountId::from([0x4; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(company);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(300);
+            contract.buy_ticket(1, 3).unwrap();
+            assert_eq!(
+                contract.set_sub_allowance(alice, 2),
+                Err(Error::NoCorporateAccount)
+            );
+            contract.set_claim_deadline(DAY).unwrap();
+            assert_eq!(
+                contract.set_sub_allowance(alice, 4),
+                Err(Error::InsufficientBalance)
+            );
+            contract.set_sub_allowance(alice, 2).unwrap();
+            assert_eq!(
+                contract.transfer_from(company, bob, 2, 2),
+                Err(Error::TicketsAllocated)
+            );
+            contract.transfer_from(company, bob, 3, 1).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            assert_eq!(
+                contract.claim_corporate_ticket(company, 1),
+                Err(Error::NoAllowance)
+            );
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            contract.claim_corporate_ticket(company, 1).unwrap();
+            assert_eq!(contract.owner_of(1), Some(alice));
+            assert_eq!(contract.sub_allowance_of(company, alice), 1);
+            assert_eq!(contract.corporate_account_of(company).unwrap().allocated, 1);
 
-        /// We test if the default constructor does its job.
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(DAY);
+            assert_eq!(
+                contract.claim_corporate_ticket(company, 2),
+                Err(Error::ClaimDeadlinePassed)
+            );
+            assert_eq!(contract.sub_allowance_of(company, alice), 0);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(company);
+            contract.transfer_from(company, bob, 2, 1).unwrap();
+            assert_eq!(contract.owner_of(2), Some(bob));
+        }
+
+        /// Test failed and oversized intents don't hold up the queue or use up the throttle
         #[ink::test]
-        fn create_event_works() {
-            let contract = TicketEvent::new(
-                100,
-                1337,
-                "Test_Name".to_string(),
-                "Test_Location".to_string(),
-                "Test_Symbol".to_string(),
-                "Test_Date".to_string(),
-                55,
-                Hash::from([0x42; 32]),
+        fn queue_throttle_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let alice = AccountId::from([0x2; 32]);
+            let bob = AccountId::from([0x3; 32]);
+            let carol = AccountId::from([0x4; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_queue_mode(true).unwrap();
+            contract.set_purchase_throttle(1).unwrap();
+            contract.open_sale().unwrap();
+
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            assert_eq!(
+                contract.enqueue_purchase(3, 2),
+                Err(Error::ThrottleExceeded)
             );
-            assert_eq!(contract.get_total_tickets(), 100);
-            assert_eq!(contract.get_name(), "Test_Name");
-            assert_eq!(contract.get_location(), "Test_Location");
-            assert_eq!(contract.owner(), AccountId::from([0x1; 32]));
-            assert_eq!(contract.get_symbol(), "Test_Symbol");
-            assert_eq!(contract.get_date(), "Test_Date");
-            assert_eq!(contract.get_price(), 55);
-            assert_eq!(contract.get_balance(), 100);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.enqueue_purchase(1, 1).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            contract.enqueue_purchase(1, 1).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(carol);
+            contract.enqueue_purchase(2, 1).unwrap();
+            assert_eq!(contract.crank_queue(5), Ok(1));
+
+            // Bob's intent fails without using up the throttle of the block
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            assert_eq!(contract.crank_queue(5), Ok(2));
+            assert_eq!(contract.refund_of(bob), 100);
+            assert_eq!(contract.owner_of(2), Some(carol));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract.set_purchase_throttle(2).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(carol);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(200);
+            contract.enqueue_purchase(3, 2).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            contract.set_purchase_throttle(1).unwrap();
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            assert_eq!(contract.crank_queue(5), Ok(1));
+            assert_eq!(contract.refund_of(carol), 200);
+            assert_eq!(contract.queue_length(), 0);
         }
 
-        /// Testings minting of tickets
+        /// Test seating layouts can be set after the sale opens until seats are sold
         #[ink::test]
-        fn minting_tests() {
-            let mut contract = TicketEvent::new(
-                0,
-                1337,
-                "Test_Name".to_string(),
-                "Test_Location".to_string(),
-                "Test_Symbol".to_string(),
-                "Test_Date".to_string(),
-                55,
-                Hash::from([0x42; 32]),
+        fn section_layout_after_open_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let buyer = AccountId::from([0x2; 32]);
+            let layout = SectionLayout {
+                rows: 1,
+                seats_per_row: 2,
+            };
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.open_sale().unwrap();
+            contract.set_section_layout(1, layout).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(buyer);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.buy_seated_tickets(1, 1, 1, false).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            assert_eq!(
+                contract.set_section_layout(
+                    1,
+                    SectionLayout {
+                        rows: 2,
+                        seats_per_row: 2,
+                    }
+                ),
+                Err(Error::SeatsSold)
             );
-            contract.mint(1, 10).unwrap();
-            assert_eq!(contract.get_total_tickets(), 10);
-            assert_eq!(contract.get_balance(), 10);
+            contract.set_section_layout(2, layout).unwrap();
         }
 
-        /// Testing changing of ownership
+        /// Test proceeds exclude queued payments and auction bids until they are settled
         #[ink::test]
-        fn ownership_tests() {
-            let mut contract = TicketEvent::new(
-                0,
-                1337,
-                "Test_Name".to_string(),
-                "Test_Location".to_string(),
-                "Test_Symbol".to_string(),
-                "Test_Date".to_string(),
-                55,
-                Hash::from([0x42; 32]),
+        #[cfg(feature = "auctions")]
+        fn withdraw_proceeds_keeps_escrow_works() {
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            let owner = AccountId::from([0x1; 32]);
+            let alice = AccountId::from([0x2; 32]);
+            let bob = AccountId::from([0x3; 32]);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(10).unwrap();
+            contract.set_event_time(0).unwrap();
+            contract.open_sale().unwrap();
+            contract.start_auction(5, 50, 2).unwrap();
+            contract.start_sealed_auction(6, 1, 50, 2, 2).unwrap();
+            contract.set_queue_mode(true).unwrap();
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                ink_env::test::callee::<ink_env::DefaultEnvironment>(),
+                400,
             );
-            contract.mint(1, 10).unwrap();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(60);
+            contract.bid(5).unwrap();
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            contract.enqueue_purchase(1, 1).unwrap();
+            let commitment = contract.sealed_bid_commitment(70, [0x1; 32]);
+            contract.commit_bid(6, commitment).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(80);
+            contract.bid(5).unwrap();
+
+            // Alice's outbid 60 is owed, Bob's 80, the queued 100 and the deposit of 100 are held
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            assert_eq!(contract.withdrawable_proceeds(), 60);
+            assert_eq!(
+                contract.withdraw_proceeds(61),
+                Err(Error::InsufficientBalance)
+            );
+            contract.crank_queue(1).unwrap();
+            assert_eq!(contract.withdrawable_proceeds(), 160);
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            contract.reveal_bid(6, 70, [0x1; 32]).unwrap();
+            // The deposit above the bid is owed to Alice
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(owner);
+            assert_eq!(contract.withdrawable_proceeds(), 160);
+            contract.settle_auction(5).unwrap();
+            assert_eq!(contract.withdrawable_proceeds(), 240);
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            contract.settle_sealed_auction(6).unwrap();
+            assert_eq!(contract.withdrawable_proceeds(), 310);
+            contract.withdraw_proceeds(310).unwrap();
+
+            contract.cancel_event().unwrap();
+            assert_eq!(contract.withdraw_proceeds(1), Err(Error::EventCancelled));
+        }
+    }
+
+    /// Storage access benchmarks at several storage scales, run with
+    /// `cargo test benches -- --ignored --nocapture`
+    #[cfg(all(test, feature = "std"))]
+    mod benches {
+        use super::*;
+        use ink_lang as ink;
+
+        const OWNER: [u8; 32] = [0x1; 32];
+        const ALICE: [u8; 32] = [0x2; 32];
+        const BOB: [u8; 32] = [0x3; 32];
+        const CAROL: [u8; 32] = [0x4; 32];
+        const SCANNER: [u8; 32] = [0x7; 32];
+
+        /// Runs `message` as `caller` and prints the storage reads and writes it performed
+        fn measure<R>(
+            contract: &mut TicketEvent,
+            scale: Balance,
+            name: &str,
+            caller: [u8; 32],
+            message: impl FnOnce(&mut TicketEvent) -> R,
+        ) -> R {
+            let callee = ink_env::test::callee::<ink_env::DefaultEnvironment>();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from(caller));
+            let (reads, writes) =
+                ink_env::test::get_contract_storage_rw::<ink_env::DefaultEnvironment>(&callee);
+            let result = message(contract);
+            let (reads_after, writes_after) =
+                ink_env::test::get_contract_storage_rw::<ink_env::DefaultEnvironment>(&callee);
+            println!(
+                "{:>7} tickets  {:<24} {:>6} reads {:>6} writes",
+                scale,
+                name,
+                reads_after - reads,
+                writes_after - writes
+            );
+            result
+        }
+
+        fn pay(value: Balance) {
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(value);
+        }
+
+        fn advance_blocks(blocks: u32) {
+            for _ in 0..blocks {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            }
+        }
+
+        /// Builds an event of `scale` tickets with every staff role granted and half of the
+        /// tickets sold to Alice
+        fn setup(scale: Balance) -> TicketEvent {
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(0);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from(OWNER));
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(scale).unwrap();
+            for role in [
+                Role::GateScanner,
+                Role::MerchStand,
+                Role::ParkingScanner,
+                Role::Vendor,
+            ] {
+                contract
+                    .set_role(role, AccountId::from(SCANNER), true)
+                    .unwrap();
+            }
+            contract.open_sale().unwrap();
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                ink_env::test::callee::<ink_env::DefaultEnvironment>(),
+                1_000_000 * scale,
+            );
+            let sold = scale / 2;
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from(ALICE));
+            pay(100 * sold);
+            contract.buy_ticket(1, sold).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from(OWNER));
             contract
-                .transfer_from(AccountId::from([0x1; 32]), AccountId::from([0x2; 32]), 1, 1)
-                .unwrap();
-            assert_eq!(contract.get_balance(), 9);
-            assert_eq!(contract.get_balance_of(AccountId::from([0x2; 32])), 1);
+        }
+
+        /// Drives the hot messages against an event of `scale` tickets with half of them sold
+        fn run_hot_paths(scale: Balance) {
+            let alice = AccountId::from(ALICE);
+            let bob = AccountId::from(BOB);
+            let mut contract = TicketEvent::new_unlinked(TicketModel::NonFungible);
+            contract.set_price(100).unwrap();
+            contract.set_total_tickets(scale).unwrap();
+            contract.set_event_time(0).unwrap();
             contract
-                .transfer_from(AccountId::from([0x1; 32]), AccountId::from([0x2; 32]), 1, 5)
+                .set_role(Role::GateScanner, AccountId::from(SCANNER), true)
                 .unwrap();
-            assert_eq!(contract.get_balance(), 4);
-            assert_eq!(contract.get_balance_of(AccountId::from([0x2; 32])), 6);
+            contract.open_sale().unwrap();
+            let sold = scale / 2;
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100 * sold);
+            contract.buy_ticket(1, sold).unwrap();
+
+            let next = sold as EventId + 1;
+            ink_env::test::set_value_transferred::<ink_env::DefaultEnvironment>(100);
+            measure(&mut contract, scale, "buy_ticket", BOB, |c| {
+                c.buy_ticket(next, 1)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "transfer_from", ALICE, |c| {
+                c.transfer_from(alice, bob, 1, 1)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "check_in", SCANNER, |c| c.check_in(2)).unwrap();
+            #[cfg(feature = "resale")]
+            {
+                measure(&mut contract, scale, "list_for_resale", ALICE, |c| {
+                    c.list_for_resale(3, 100)
+                })
+                .unwrap();
+                measure(&mut contract, scale, "buy_resale", BOB, |c| c.buy_resale(3)).unwrap();
+            }
+            let group = (4..14).map(|ticket_id| (ticket_id, alice)).collect();
+            measure(&mut contract, scale, "verify_batch", SCANNER, |c| {
+                c.verify_batch(group)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "snapshot_chunk", SCANNER, |c| {
+                c.snapshot_chunk(0, 100)
+            });
+            measure(&mut contract, scale, "build_snapshot", OWNER, |c| {
+                c.build_snapshot(100)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "changes_since", SCANNER, |c| {
+                c.changes_since(0, 100)
+            });
+            measure(&mut contract, scale, "cancel_event", OWNER, |c| {
+                c.cancel_event()
+            })
+            .unwrap();
+            measure(&mut contract, scale, "process_refunds", OWNER, |c| {
+                c.process_refunds(100)
+            })
+            .unwrap();
         }
 
-        /// Testing transfering tickets with no ID should panic
-        #[ink::test]
-        #[should_panic(expected = "TokenNotFound")]
-        fn transfering_tests() {
-            let mut contract = TicketEvent::new(
-                0,
-                1337,
-                "Test_Name".to_string(),
-                "Test_Location".to_string(),
-                "Test_Symbol".to_string(),
-                "Test_Date".to_string(),
-                55,
-                Hash::from([0x42; 32]),
-            );
-            contract.mint(1, 10).unwrap();
+        /// Drives the configuration messages and every way of buying from the organizer
+        fn run_sales(scale: Balance) {
+            let bob = AccountId::from(BOB);
+            let mut contract = setup(scale);
+            let next = (scale / 2) as EventId + 1;
+            measure(&mut contract, scale, "set_event_details", OWNER, |c| {
+                c.set_event_details(
+                    "Name".into(),
+                    "Location".into(),
+                    "SYM".into(),
+                    "Date".into(),
+                )
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_price", OWNER, |c| {
+                c.set_price(100)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_tax", OWNER, |c| {
+                c.set_tax(1_000, "NL".into())
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_concession", OWNER, |c| {
+                c.set_concession(Concession::Student, 50, 10)
+            })
+            .unwrap();
+            measure(
+                &mut contract,
+                scale,
+                "set_last_minute_discount",
+                OWNER,
+                |c| {
+                    c.set_last_minute_discount(Some(LastMinuteDiscount {
+                        window: DAY,
+                        sell_through_target_bps: 5_000,
+                        discount_bps: 1_000,
+                    }))
+                },
+            )
+            .unwrap();
+            measure(&mut contract, scale, "set_price_override", OWNER, |c| {
+                c.set_price_override(next + 9, Some(150))
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_carbon_offset", OWNER, |c| {
+                c.set_carbon_offset(3, Some(AccountId::from(CAROL)))
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_voucher_terms", OWNER, |c| {
+                c.set_voucher_terms(20, 10)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_parking_terms", OWNER, |c| {
+                c.set_parking_terms(30, 10)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_add_on", OWNER, |c| {
+                c.set_add_on(1, 50, 10)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_pass_days", OWNER, |c| {
+                c.set_pass_days(next, 0b11)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_ticket_section", OWNER, |c| {
+                c.set_ticket_section(next, 2)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_section_layout", OWNER, |c| {
+                c.set_section_layout(
+                    1,
+                    SectionLayout {
+                        rows: 2,
+                        seats_per_row: 5,
+                    },
+                )
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_circuit_breaker", OWNER, |c| {
+                c.set_circuit_breaker(0, 0)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "reset_circuit_breaker", OWNER, |c| {
+                c.reset_circuit_breaker()
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_purchase_throttle", OWNER, |c| {
+                c.set_purchase_throttle(0)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_pricing_strategy", OWNER, |c| {
+                c.set_pricing_strategy(None)
+            })
+            .unwrap();
+            // A registered hook is called by every later message, so only its storage is measured
+            measure(&mut contract, scale, "register_hook", OWNER, |c| {
+                c.register_hook(HookPoint::AfterCheckIn, AccountId::from(CAROL), 1_000)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "unregister_hook", OWNER, |c| {
+                c.unregister_hook(HookPoint::AfterCheckIn)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_approval_for_all", ALICE, |c| {
+                c.set_approval_for_all(bob, true)
+            });
+
+            let options = PurchaseOptions {
+                concession: None,
+                carbon_offset: true,
+                vouchers: 1,
+                parking: 1,
+                credit: 10,
+            };
+            pay(contract.get_quote_with_options(next, 1, options).unwrap());
+            measure(&mut contract, scale, "buy_ticket_with_options", BOB, |c| {
+                c.buy_ticket_with_options(next, 1, options)
+            })
+            .unwrap();
+            let concession = PurchaseOptions {
+                concession: Some(Concession::Student),
+                ..PurchaseOptions::default()
+            };
+            pay(contract
+                .get_quote_with_options(next + 1, 1, concession)
+                .unwrap());
+            measure(&mut contract, scale, "buy_concession_ticket", BOB, |c| {
+                c.buy_concession_ticket(next + 1, 1, Concession::Student)
+            })
+            .unwrap();
+            pay(contract.get_quote(next + 2, 2).unwrap());
+            measure(&mut contract, scale, "buy_seated_tickets", BOB, |c| {
+                c.buy_seated_tickets(next + 2, 2, 1, false)
+            })
+            .unwrap();
+            // Prices plus 10% tax
+            pay(22);
+            measure(&mut contract, scale, "buy_vouchers", BOB, |c| {
+                c.buy_vouchers(1)
+            })
+            .unwrap();
+            pay(33);
+            measure(&mut contract, scale, "buy_parking", BOB, |c| {
+                c.buy_parking(1)
+            })
+            .unwrap();
+            pay(10);
+            measure(&mut contract, scale, "top_up_credit", BOB, |c| {
+                c.top_up_credit()
+            })
+            .unwrap();
+            pay(55);
+            measure(&mut contract, scale, "buy_add_on", BOB, |c| {
+                c.buy_add_on(next, 1)
+            })
+            .unwrap();
+
+            measure(&mut contract, scale, "set_queue_mode", OWNER, |c| {
+                c.set_queue_mode(true)
+            })
+            .unwrap();
+            pay(contract.get_quote(next + 4, 1).unwrap());
+            measure(&mut contract, scale, "enqueue_purchase", BOB, |c| {
+                c.enqueue_purchase(next + 4, 1)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "crank_queue", OWNER, |c| {
+                c.crank_queue(10)
+            })
+            .unwrap();
+            contract.set_queue_mode(false).unwrap();
+            measure(&mut contract, scale, "mint", OWNER, |c| {
+                c.mint(scale as EventId + 1, 1)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "withdraw_carbon_offset", OWNER, |c| {
+                c.withdraw_carbon_offset()
+            })
+            .unwrap();
+            read_all(&mut contract, scale, next);
+        }
+
+        /// Drives the messages that move sold tickets between attendees
+        fn run_secondary_market(scale: Balance) {
+            let alice = AccountId::from(ALICE);
+            let bob = AccountId::from(BOB);
+            let carol = AccountId::from(CAROL);
+            let mut contract = setup(scale);
+            let next = (scale / 2) as EventId + 1;
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            contract.set_approval_for_all(bob, true);
+            measure(&mut contract, scale, "transfer_from_operator", BOB, |c| {
+                c.transfer_from(alice, carol, 1, 1)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "offer_transfer", ALICE, |c| {
+                c.offer_transfer(2, bob, DAY)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "accept_transfer", BOB, |c| {
+                c.accept_transfer(2)
+            })
+            .unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            contract.offer_transfer(3, bob, DAY).unwrap();
+            measure(&mut contract, scale, "cancel_transfer", ALICE, |c| {
+                c.cancel_transfer(3)
+            })
+            .unwrap();
+            #[cfg(feature = "resale")]
+            {
+                measure(&mut contract, scale, "set_resale_share", OWNER, |c| {
+                    c.set_resale_share(1_000)
+                })
+                .unwrap();
+                measure(&mut contract, scale, "set_resale_floor", OWNER, |c| {
+                    c.set_resale_floor(5_000)
+                })
+                .unwrap();
+                ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+                contract.list_for_resale(4, 100).unwrap();
+                measure(&mut contract, scale, "cancel_listing", ALICE, |c| {
+                    c.cancel_listing(4)
+                })
+                .unwrap();
+            }
+            measure(&mut contract, scale, "set_sponsorship", OWNER, |c| {
+                c.set_sponsorship(
+                    next + 9,
+                    Sponsorship {
+                        entitlement: "Banner".into(),
+                        locked_until: 0,
+                    },
+                )
+            })
+            .unwrap();
+            measure(&mut contract, scale, "reissue_ticket", OWNER, |c| {
+                c.reissue_ticket(5, scale as EventId + 1, carol)
+            })
+            .unwrap();
+
             contract
-                .transfer_from(AccountId::from([0x1; 32]), AccountId::from([0x2; 32]), 2, 1)
+                .set_section_layout(
+                    1,
+                    SectionLayout {
+                        rows: 2,
+                        seats_per_row: 2,
+                    },
+                )
                 .unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(bob);
+            pay(contract.get_quote(next, 2).unwrap());
+            contract.buy_seated_tickets(next, 2, 1, false).unwrap();
+            measure(&mut contract, scale, "request_seat_swap", BOB, |c| {
+                c.request_seat_swap(next, 1, 1, 2)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "cancel_seat_swap", BOB, |c| {
+                c.cancel_seat_swap(next)
+            })
+            .unwrap();
+            contract.request_seat_swap(next, 1, 1, 2).unwrap();
+            measure(&mut contract, scale, "accept_seat_swap", BOB, |c| {
+                c.accept_seat_swap(next + 1, next)
+            })
+            .unwrap();
+
+            measure(&mut contract, scale, "set_claim_deadline", ALICE, |c| {
+                c.set_claim_deadline(DAY)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_sub_allowance", ALICE, |c| {
+                c.set_sub_allowance(bob, 2)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "claim_corporate_ticket", BOB, |c| {
+                c.claim_corporate_ticket(alice, 6)
+            })
+            .unwrap();
         }
 
-        /// Test removing token with ID from owner
-        #[ink::test]
-        fn remove_token_tests() {
-            let mut contract = TicketEvent::new(
-                0,
-                1337,
-                "Test_Name".to_string(),
-                "Test_Location".to_string(),
-                "Test_Symbol".to_string(),
-                "Test_Date".to_string(),
-                55,
-                Hash::from([0x42; 32]),
-            );
-            contract.mint(1, 10).unwrap();
-            contract.mint(2, 10).unwrap();
+        /// Drives the English, sealed-bid and seat upgrade auctions from start to settlement
+        #[cfg(feature = "auctions")]
+        fn run_auctions(scale: Balance) {
+            let mut contract = setup(scale);
+            let next = (scale / 2) as EventId + 1;
+            measure(&mut contract, scale, "set_anti_sniping", OWNER, |c| {
+                c.set_anti_sniping(Some(AntiSniping {
+                    window: 1,
+                    extension: 1,
+                }))
+            })
+            .unwrap();
+            measure(&mut contract, scale, "start_auction", OWNER, |c| {
+                c.start_auction(next, 100, 3)
+            })
+            .unwrap();
+            pay(100);
+            measure(&mut contract, scale, "bid", BOB, |c| c.bid(next)).unwrap();
+            pay(150);
+            measure(&mut contract, scale, "bid_outbidding", CAROL, |c| {
+                c.bid(next)
+            })
+            .unwrap();
+            advance_blocks(4);
+            measure(&mut contract, scale, "settle_auction", OWNER, |c| {
+                c.settle_auction(next)
+            })
+            .unwrap();
+
+            measure(&mut contract, scale, "start_sealed_auction", OWNER, |c| {
+                c.start_sealed_auction(next + 1, 2, 50, 2, 2)
+            })
+            .unwrap();
+            let salt = [0x9; 32];
+            let commitment = measure(&mut contract, scale, "sealed_bid_commitment", BOB, |c| {
+                c.sealed_bid_commitment(80, salt)
+            });
+            pay(100);
+            measure(&mut contract, scale, "commit_bid", BOB, |c| {
+                c.commit_bid(next + 1, commitment)
+            })
+            .unwrap();
+            advance_blocks(2);
+            measure(&mut contract, scale, "reveal_bid", BOB, |c| {
+                c.reveal_bid(next + 1, 80, salt)
+            })
+            .unwrap();
+            advance_blocks(2);
+            measure(&mut contract, scale, "settle_sealed_auction", OWNER, |c| {
+                c.settle_sealed_auction(next + 1)
+            })
+            .unwrap();
+
             contract
-                .remove_token_from(AccountId::from([0x1; 32]), 1)
+                .set_section_layout(
+                    1,
+                    SectionLayout {
+                        rows: 1,
+                        seats_per_row: 3,
+                    },
+                )
                 .unwrap();
-            assert_eq!(contract.get_balance(), 19);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from(BOB));
+            pay(contract.get_quote(next + 3, 1).unwrap());
+            contract.buy_seated_tickets(next + 3, 1, 1, false).unwrap();
+            let seat = Seat {
+                section: 1,
+                row: 1,
+                number: 3,
+            };
+            measure(&mut contract, scale, "start_upgrade_auction", OWNER, |c| {
+                c.start_upgrade_auction(seat, 10, 2)
+            })
+            .unwrap();
+            pay(10);
+            measure(&mut contract, scale, "bid_upgrade", BOB, |c| {
+                c.bid_upgrade(seat, next + 3)
+            })
+            .unwrap();
+            advance_blocks(2);
+            measure(&mut contract, scale, "settle_upgrade_auction", OWNER, |c| {
+                c.settle_upgrade_auction(seat)
+            })
+            .unwrap();
+        }
 
+        /// Drives the messages used at the gates, stands and car park on the day of the event
+        fn run_event_day(scale: Balance) {
+            let alice = AccountId::from(ALICE);
+            let mut contract = setup(scale);
+            let next = (scale / 2) as EventId + 1;
+            contract.set_add_on(1, 50, 10).unwrap();
+            contract.set_voucher_terms(20, 10).unwrap();
+            contract.set_parking_terms(30, 10).unwrap();
+            measure(
+                &mut contract,
+                scale,
+                "set_unused_refund_policy",
+                OWNER,
+                |c| {
+                    c.set_unused_refund_policy(Some(UnusedRefundPolicy {
+                        refund_bps: 2_000,
+                        reserve_bps: 2_000,
+                        window: DAY,
+                    }))
+                },
+            )
+            .unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(AccountId::from(BOB));
+            pay(100);
+            contract.buy_ticket(next, 1).unwrap();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(alice);
+            pay(50);
+            contract.buy_add_on(3, 1).unwrap();
+            pay(20);
+            contract.buy_vouchers(1).unwrap();
+            pay(30);
+            contract.buy_parking(1).unwrap();
+            pay(20);
+            contract.top_up_credit().unwrap();
+            measure(&mut contract, scale, "set_attendee_data", ALICE, |c| {
+                c.set_attendee_data(1, Hash::from([0x5; 32]))
+            })
+            .unwrap();
+            measure(&mut contract, scale, "clear_attendee_data", ALICE, |c| {
+                c.clear_attendee_data(1)
+            })
+            .unwrap();
             contract
-                .transfer_from(AccountId::from([0x1; 32]), AccountId::from([0x2; 32]), 2, 5)
+                .set_attendee_data(2, Hash::from([0x5; 32]))
                 .unwrap();
-            assert_eq!(contract.get_balance(), 14);
-            assert_eq!(contract.total_tickets, 20);
+
+            measure(&mut contract, scale, "set_event_time", OWNER, |c| {
+                c.set_event_time(0)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "check_in", SCANNER, |c| c.check_in(1)).unwrap();
+            measure(&mut contract, scale, "check_out", SCANNER, |c| {
+                c.check_out(1)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "check_in_reentry", SCANNER, |c| {
+                c.check_in(1)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "check_in_add_on", SCANNER, |c| {
+                c.check_in_add_on(3, 1)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "redeem_voucher", SCANNER, |c| {
+                c.redeem_voucher(alice, 1)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "check_in_vehicle", SCANNER, |c| {
+                c.check_in_vehicle(alice)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "spend_credit", SCANNER, |c| {
+                c.spend_credit(alice, 5)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "refund_unspent_credit", ALICE, |c| {
+                c.refund_unspent_credit()
+            })
+            .unwrap();
+            measure(&mut contract, scale, "return_unused_ticket", BOB, |c| {
+                c.return_unused_ticket(next)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "purge_attendee_data", OWNER, |c| {
+                c.purge_attendee_data(100)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "withdraw_proceeds", OWNER, |c| {
+                c.withdraw_proceeds(1)
+            })
+            .unwrap();
+            read_all(&mut contract, scale, 1);
+        }
+
+        /// Drives the guardian timelocks through to the refunds they leave behind
+        #[cfg(feature = "governance")]
+        fn run_governance(scale: Balance) {
+            let mut contract = setup(scale);
+            measure(&mut contract, scale, "set_guardian", OWNER, |c| {
+                c.set_guardian(AccountId::from(CAROL))
+            })
+            .unwrap();
+            let owner = AccountId::from(OWNER);
+            measure(
+                &mut contract,
+                scale,
+                "announce_emergency_withdraw",
+                OWNER,
+                |c| c.announce_emergency_withdraw(owner, 1),
+            )
+            .unwrap();
+            measure(
+                &mut contract,
+                scale,
+                "cancel_emergency_withdraw",
+                CAROL,
+                |c| c.cancel_emergency_withdraw(),
+            )
+            .unwrap();
+            contract.announce_emergency_withdraw(owner, 1).unwrap();
+            let target = ReductionTarget::TicketIds {
+                first: 1,
+                count: 10,
+            };
+            measure(
+                &mut contract,
+                scale,
+                "announce_capacity_reduction",
+                OWNER,
+                |c| c.announce_capacity_reduction(target),
+            )
+            .unwrap();
+            measure(
+                &mut contract,
+                scale,
+                "cancel_capacity_reduction",
+                CAROL,
+                |c| c.cancel_capacity_reduction(),
+            )
+            .unwrap();
+            contract.announce_capacity_reduction(target).unwrap();
+
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(
+                EMERGENCY_WITHDRAWAL_DELAY,
+            );
+            measure(&mut contract, scale, "emergency_withdraw", OWNER, |c| {
+                c.emergency_withdraw()
+            })
+            .unwrap();
+            measure(
+                &mut contract,
+                scale,
+                "execute_capacity_reduction",
+                OWNER,
+                |c| c.execute_capacity_reduction(),
+            )
+            .unwrap();
+            measure(&mut contract, scale, "claim_refund", ALICE, |c| {
+                c.claim_refund()
+            })
+            .unwrap();
+        }
+
+        /// Drives the refund and claims messages that wind an event down
+        fn run_wind_down(scale: Balance) {
+            let mut contract = setup(scale);
+            measure(&mut contract, scale, "set_dead_man_period", OWNER, |c| {
+                c.set_dead_man_period(Some(DAY))
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_refund_condition", OWNER, |c| {
+                c.set_refund_condition(Some(RefundCondition {
+                    oracle: AccountId::from(CAROL),
+                    from: 0,
+                    until: DAY,
+                }))
+            })
+            .unwrap();
+            measure(&mut contract, scale, "set_claims_deadline", OWNER, |c| {
+                c.set_claims_deadline(MIN_CLAIMS_PERIOD)
+            })
+            .unwrap();
+            measure(&mut contract, scale, "cancel_event", OWNER, |c| {
+                c.cancel_event()
+            })
+            .unwrap();
+            measure(
+                &mut contract,
+                scale,
+                "claim_cancellation_refund",
+                ALICE,
+                |c| c.claim_cancellation_refund(),
+            )
+            .unwrap();
+
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(MIN_CLAIMS_PERIOD);
+            measure(&mut contract, scale, "sweep_unclaimed", OWNER, |c| {
+                c.sweep_unclaimed()
+            })
+            .unwrap();
+            measure(&mut contract, scale, "cleanup_refund_ledger", OWNER, |c| {
+                c.cleanup_refund_ledger(100)
+            })
+            .unwrap();
+        }
+
+        /// Drives the refund attendees can take themselves once the organizer stops responding
+        fn run_abandonment(scale: Balance) {
+            let mut contract = setup(scale);
+            contract.set_dead_man_period(Some(DAY)).unwrap();
+            ink_env::test::set_block_timestamp::<ink_env::DefaultEnvironment>(DAY);
+            measure(&mut contract, scale, "refund_abandoned", ALICE, |c| {
+                c.refund_abandoned()
+            })
+            .unwrap();
+        }
+
+        /// Runs every read-only message against the state left by a phase
+        fn read_all(contract: &mut TicketEvent, scale: Balance, ticket_id: EventId) {
+            let alice = AccountId::from(ALICE);
+            let bob = AccountId::from(BOB);
+            measure(contract, scale, "get_ticket_contract", OWNER, |c| {
+                c.get_ticket_contract()
+            });
+            measure(contract, scale, "is_sale_open", OWNER, |c| c.is_sale_open());
+            measure(contract, scale, "owner", OWNER, |c| c.owner());
+            measure(contract, scale, "get_name", OWNER, |c| c.get_name());
+            measure(contract, scale, "get_location", OWNER, |c| c.get_location());
+            measure(contract, scale, "get_total_tickets", OWNER, |c| {
+                c.get_total_tickets()
+            });
+            measure(contract, scale, "get_price", OWNER, |c| c.get_price());
+            measure(contract, scale, "get_date", OWNER, |c| c.get_date());
+            measure(contract, scale, "get_symbol", OWNER, |c| c.get_symbol());
+            measure(contract, scale, "get_balance", ALICE, |c| c.get_balance());
+            measure(contract, scale, "get_balance_of", OWNER, |c| {
+                c.get_balance_of(alice)
+            });
+            measure(contract, scale, "is_approved_for_all", OWNER, |c| {
+                c.is_approved_for_all(alice, bob)
+            });
+            measure(contract, scale, "exists", OWNER, |c| c.exists(ticket_id));
+            measure(contract, scale, "get_bool", OWNER, |c| c.get_bool());
+            measure(contract, scale, "refund_of", OWNER, |c| c.refund_of(alice));
+            measure(contract, scale, "get_claims_deadline", OWNER, |c| {
+                c.get_claims_deadline()
+            });
+            measure(contract, scale, "get_event_time", OWNER, |c| {
+                c.get_event_time()
+            });
+            measure(contract, scale, "get_attendee_data", ALICE, |c| {
+                c.get_attendee_data(ticket_id)
+            });
+            measure(contract, scale, "get_hook", OWNER, |c| {
+                c.get_hook(HookPoint::AfterCheckIn)
+            });
+            measure(contract, scale, "is_paused", OWNER, |c| c.is_paused());
+            measure(contract, scale, "get_pricing_strategy", OWNER, |c| {
+                c.get_pricing_strategy()
+            });
+            measure(contract, scale, "get_tickets_sold", OWNER, |c| {
+                c.get_tickets_sold()
+            });
+            measure(contract, scale, "get_concession", OWNER, |c| {
+                c.get_concession(Concession::Student)
+            });
+            measure(contract, scale, "concession_of", OWNER, |c| {
+                c.concession_of(ticket_id)
+            });
+            measure(contract, scale, "get_last_minute_discount", OWNER, |c| {
+                c.get_last_minute_discount()
+            });
+            measure(contract, scale, "active_discount_bps", OWNER, |c| {
+                c.active_discount_bps()
+            });
+            measure(contract, scale, "get_receipt", OWNER, |c| c.get_receipt(1));
+            measure(contract, scale, "get_invoice_count", OWNER, |c| {
+                c.get_invoice_count()
+            });
+            measure(contract, scale, "projected_revenue", OWNER, |c| {
+                c.projected_revenue()
+            });
+            measure(contract, scale, "withdrawable_proceeds", OWNER, |c| {
+                c.withdrawable_proceeds()
+            });
+            measure(contract, scale, "get_quote", OWNER, |c| {
+                c.get_quote(ticket_id, 1)
+            });
+            measure(contract, scale, "get_quote_with_options", OWNER, |c| {
+                c.get_quote_with_options(ticket_id, 1, PurchaseOptions::default())
+            });
+            measure(contract, scale, "get_tax", OWNER, |c| c.get_tax());
+            measure(contract, scale, "get_tax_collected", OWNER, |c| {
+                c.get_tax_collected()
+            });
+            measure(contract, scale, "price_override_of", OWNER, |c| {
+                c.price_override_of(ticket_id)
+            });
+            measure(contract, scale, "ticket_model", OWNER, |c| c.ticket_model());
+            measure(contract, scale, "owner_of", OWNER, |c| {
+                c.owner_of(ticket_id)
+            });
+            measure(contract, scale, "get_carbon_offset", OWNER, |c| {
+                c.get_carbon_offset()
+            });
+            measure(contract, scale, "get_carbon_offset_totals", OWNER, |c| {
+                c.get_carbon_offset_totals()
+            });
+            measure(contract, scale, "get_voucher_terms", OWNER, |c| {
+                c.get_voucher_terms()
+            });
+            measure(contract, scale, "has_role", OWNER, |c| {
+                c.has_role(Role::GateScanner, AccountId::from(SCANNER))
+            });
+            measure(contract, scale, "vouchers_of", OWNER, |c| {
+                c.vouchers_of(alice)
+            });
+            measure(contract, scale, "get_parking_terms", OWNER, |c| {
+                c.get_parking_terms()
+            });
+            measure(contract, scale, "parking_passes_of", OWNER, |c| {
+                c.parking_passes_of(alice)
+            });
+            measure(contract, scale, "credit_of", OWNER, |c| c.credit_of(alice));
+            measure(contract, scale, "get_credit_totals", OWNER, |c| {
+                c.get_credit_totals()
+            });
+            measure(contract, scale, "pass_days_of", OWNER, |c| {
+                c.pass_days_of(ticket_id)
+            });
+            measure(contract, scale, "remaining_days_of", OWNER, |c| {
+                c.remaining_days_of(ticket_id)
+            });
+            measure(contract, scale, "check_in_of", OWNER, |c| {
+                c.check_in_of(ticket_id)
+            });
+            measure(contract, scale, "section_of", OWNER, |c| {
+                c.section_of(ticket_id)
+            });
+            measure(contract, scale, "current_attendance", OWNER, |c| {
+                c.current_attendance()
+            });
+            measure(contract, scale, "section_attendance", OWNER, |c| {
+                c.section_attendance(0)
+            });
+            measure(contract, scale, "get_unused_refund_policy", OWNER, |c| {
+                c.get_unused_refund_policy()
+            });
+            measure(contract, scale, "get_unused_refund_reserve", OWNER, |c| {
+                c.get_unused_refund_reserve()
+            });
+            #[cfg(feature = "resale")]
+            {
+                measure(contract, scale, "get_resale_share", OWNER, |c| {
+                    c.get_resale_share()
+                });
+                measure(contract, scale, "get_resale_floor", OWNER, |c| {
+                    c.get_resale_floor()
+                });
+                measure(contract, scale, "resale_floor_of", OWNER, |c| {
+                    c.resale_floor_of(ticket_id)
+                });
+                measure(contract, scale, "get_listing", OWNER, |c| {
+                    c.get_listing(ticket_id)
+                });
+            }
+            #[cfg(feature = "auctions")]
+            {
+                measure(contract, scale, "get_auction", OWNER, |c| {
+                    c.get_auction(ticket_id)
+                });
+                measure(contract, scale, "get_anti_sniping", OWNER, |c| {
+                    c.get_anti_sniping()
+                });
+                measure(contract, scale, "get_sealed_auction", OWNER, |c| {
+                    c.get_sealed_auction(ticket_id)
+                });
+            }
+            measure(contract, scale, "payment_of", OWNER, |c| {
+                c.payment_of(alice)
+            });
+            measure(contract, scale, "is_cancelled", OWNER, |c| c.is_cancelled());
+            measure(contract, scale, "is_queue_mode", OWNER, |c| {
+                c.is_queue_mode()
+            });
+            measure(contract, scale, "queued_purchase", OWNER, |c| {
+                c.queued_purchase(0)
+            });
+            measure(contract, scale, "queue_length", OWNER, |c| c.queue_length());
+            measure(contract, scale, "get_purchase_throttle", OWNER, |c| {
+                c.get_purchase_throttle()
+            });
+            measure(contract, scale, "get_dead_man_period", OWNER, |c| {
+                c.get_dead_man_period()
+            });
+            measure(contract, scale, "get_last_admin_action", OWNER, |c| {
+                c.get_last_admin_action()
+            });
+            measure(contract, scale, "is_abandoned", OWNER, |c| c.is_abandoned());
+            #[cfg(feature = "governance")]
+            {
+                measure(contract, scale, "get_guardian", OWNER, |c| c.get_guardian());
+                measure(contract, scale, "get_pending_withdrawal", OWNER, |c| {
+                    c.get_pending_withdrawal()
+                });
+                measure(contract, scale, "get_pending_reduction", OWNER, |c| {
+                    c.get_pending_reduction()
+                });
+            }
+            measure(contract, scale, "get_refund_condition", OWNER, |c| {
+                c.get_refund_condition()
+            });
+            measure(contract, scale, "get_section_layout", OWNER, |c| {
+                c.get_section_layout(1)
+            });
+            measure(contract, scale, "seat_of", OWNER, |c| c.seat_of(ticket_id));
+            measure(contract, scale, "ticket_at", OWNER, |c| {
+                c.ticket_at(1, 1, 1)
+            });
+            measure(contract, scale, "get_seat_swap", OWNER, |c| {
+                c.get_seat_swap(ticket_id)
+            });
+            #[cfg(feature = "auctions")]
+            measure(contract, scale, "get_upgrade_auction", OWNER, |c| {
+                c.get_upgrade_auction(Seat {
+                    section: 1,
+                    row: 1,
+                    number: 1,
+                })
+            });
+            measure(contract, scale, "ticket_status", OWNER, |c| {
+                c.ticket_status(ticket_id)
+            });
+            measure(contract, scale, "get_add_on", OWNER, |c| c.get_add_on(1));
+            measure(contract, scale, "add_ons_of", OWNER, |c| {
+                c.add_ons_of(ticket_id)
+            });
+            measure(contract, scale, "unredeemed_add_ons_of", OWNER, |c| {
+                c.unredeemed_add_ons_of(ticket_id)
+            });
+            measure(contract, scale, "sponsorship_of", OWNER, |c| {
+                c.sponsorship_of(ticket_id)
+            });
+            measure(contract, scale, "pending_transfer_of", OWNER, |c| {
+                c.pending_transfer_of(ticket_id)
+            });
+            measure(contract, scale, "get_issued_count", OWNER, |c| {
+                c.get_issued_count()
+            });
+            measure(contract, scale, "get_snapshot", OWNER, |c| c.get_snapshot());
+            measure(contract, scale, "get_change_count", OWNER, |c| {
+                c.get_change_count()
+            });
+            measure(contract, scale, "corporate_account_of", OWNER, |c| {
+                c.corporate_account_of(alice)
+            });
+            measure(contract, scale, "sub_allowance_of", OWNER, |c| {
+                c.sub_allowance_of(alice, bob)
+            });
+        }
+
+        /// Drives every message that can run off-chain. `link_ticket_contract` and
+        /// `trigger_refund_condition` call other contracts and are left to on-chain runs.
+        fn run(scale: Balance) {
+            run_hot_paths(scale);
+            run_sales(scale);
+            run_secondary_market(scale);
+            #[cfg(feature = "auctions")]
+            run_auctions(scale);
+            run_event_day(scale);
+            #[cfg(feature = "governance")]
+            run_governance(scale);
+            run_wind_down(scale);
+            run_abandonment(scale);
+        }
+
+        #[ink::test]
+        #[ignore]
+        fn storage_access_100() {
+            run(100);
+        }
+
+        #[ink::test]
+        #[ignore]
+        fn storage_access_10k() {
+            run(10_000);
+        }
+
+        #[ink::test]
+        #[ignore]
+        fn storage_access_100k() {
+            run(100_000);
         }
     }
 }
@@ -5,14 +5,8 @@ use ink_lang as ink;
 
 #[ink::contract]
 mod ticket {
-    use ink_env::call::FromAccountId;
     use ink_storage::traits::SpreadAllocate;
 
-    impl SpreadAllocate for TicketRef {
-        fn allocate_spread(_ptr: &mut ink_primitives::KeyPtr) -> Self {
-            FromAccountId::from_account_id([0; 32].into())
-        }
-    }
     /// Defines the storage of your contract.
     /// Add new fields to the below struct in order
     /// to add new static storage fields to your contract.